@@ -0,0 +1,100 @@
+//! Compares the throughput of the default field of gf-complete for `w = 32`
+//! and of the composite field GF((2^16)^2), both built by `GaloisFieldBuilder`.
+//!
+//! The fields are compared by their own multiplications, and by `Technique::Matrix`
+//! codes built with the fields, so that both codes take the same path.
+//!
+//! Run with `cargo bench --bench composite_field`.
+
+use std::hint::black_box;
+use std::num::NonZeroI32;
+use std::time::{Duration, Instant};
+
+use jerasure_rs::CodeWord;
+use jerasure_rs::erasure::{CodingMethod, ErasureCode, ErasureCodeBuilder, Technique};
+use jerasure_rs::galois::{GaloisField, GaloisFieldBuilder, MultType};
+
+const K: i32 = 10;
+const M: i32 = 4;
+const BLK_SIZE: usize = 1 << 20;
+const N_PRODUCTS: usize = 1 << 16;
+const ITERATIONS: u32 = 20;
+
+fn build(gf: GaloisField) -> ErasureCode {
+    ErasureCodeBuilder::new()
+        .coding_method(CodingMethod::ReedSolVand)
+        .k(NonZeroI32::new(K).unwrap())
+        .m(NonZeroI32::new(M).unwrap())
+        .galois_field(gf)
+        .tech(Technique::Matrix)
+        .build()
+        .unwrap()
+}
+
+/// Runs `f` for `ITERATIONS` times and reports the throughput over `bytes` bytes.
+fn bench(name: &str, bytes: usize, mut f: impl FnMut()) {
+    f();
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    let elapsed: Duration = start.elapsed() / ITERATIONS;
+    let mib = bytes as f64 / f64::from(1 << 20);
+    println!(
+        "{name:<36} {:>10.3} ms {:>10.1} MiB/s",
+        elapsed.as_secs_f64() * 1e3,
+        mib / elapsed.as_secs_f64()
+    );
+}
+
+fn main() {
+    let default = GaloisFieldBuilder::new().w(CodeWord::W32).build().unwrap();
+    let base = GaloisFieldBuilder::new().w(CodeWord::W16).build().unwrap();
+    let composite = GaloisFieldBuilder::new()
+        .w(CodeWord::W32)
+        .mult_type(MultType::Composite)
+        .args(2, 0)
+        .base_field(base)
+        .build()
+        .unwrap();
+    let data: Vec<Vec<u8>> = (0..K)
+        .map(|_| rand::random_iter().take(BLK_SIZE).collect())
+        .collect();
+    let pairs: Vec<(i32, i32)> = (0..N_PRODUCTS).map(|_| rand::random()).collect();
+    let erased = [0, 1, 2, K];
+
+    for (name, gf) in [("default", default), ("composite (2^16)^2", composite)] {
+        // the field arithmetic, where a product is of two 4-byte elements
+        bench(&format!("multiply {name}"), N_PRODUCTS * 4, || {
+            for &(a, b) in black_box(&pairs) {
+                black_box(gf.multiply(a, b));
+            }
+        });
+        let multiply_by = pairs[0].0;
+        let mut dest = vec![0_u8; BLK_SIZE];
+        bench(&format!("region_multiply {name}"), BLK_SIZE, || {
+            gf.region_multiply(black_box(&data[0]), multiply_by, 0, &mut dest)
+                .unwrap();
+        });
+
+        let ec = build(gf);
+        let mut code = vec![vec![0_u8; BLK_SIZE]; M as usize];
+        bench(&format!("encode {name}"), K as usize * BLK_SIZE, || {
+            ec.encode(black_box(&data), &mut code).unwrap();
+        });
+        let (mut erased_data, mut erased_code) = (data.clone(), code.clone());
+        bench(&format!("decode {name}"), K as usize * BLK_SIZE, || {
+            for &e in &erased {
+                let blk = match usize::try_from(e - K) {
+                    Ok(i) => &mut erased_code[i],
+                    Err(_) => &mut erased_data[e as usize],
+                };
+                blk.fill(0);
+            }
+            ec.decode(&mut erased_data, &mut erased_code, black_box(&erased))
+                .unwrap();
+        });
+        assert_eq!(erased_data, data);
+        assert_eq!(erased_code, code);
+    }
+}
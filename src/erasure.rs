@@ -6,12 +6,31 @@
 //! For more information, see the [jerasure documentation](https://github.com/tsuraan/Jerasure/blob/414c96ef2b9934953b6facb31d803d79b1dd1405/Manual.pdf)
 
 use ::std::os::raw::c_int;
-use std::num::NonZeroI32;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::num::{NonZeroI32, NonZeroUsize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
 
+use crate::galois::GaloisField;
 use crate::{CodeWord, Error};
 
 use iter_tools::Itertools;
 
+// `jerasure-sys` does not generate bindings for `liberation.h`,
+// so the bit-matrix constructors are declared here.
+unsafe extern "C" {
+    fn liberation_coding_bitmatrix(k: c_int, w: c_int) -> *mut c_int;
+    fn liber8tion_coding_bitmatrix(k: c_int) -> *mut c_int;
+    fn blaum_roth_coding_bitmatrix(k: c_int, w: c_int) -> *mut c_int;
+    // matrices are released by `jerasure_free_matrix`, so they must be allocated by `malloc`
+    fn malloc(size: usize) -> *mut std::ffi::c_void;
+}
+
+/// The maximum number of erasure patterns, $\binom{k+m}{m}$, of a `Custom` code,
+/// which bounds the time to check that the coding matrix is MDS.
+const MAX_CUSTOM_PATTERNS: u64 = 1 << 16;
+
 #[derive(Debug, Clone, Copy, Default)]
 /// The `Technique` is used to represent the technique used to encode and decode the data.
 ///
@@ -27,6 +46,7 @@ pub enum Technique {
     ///
     /// # Requires
     /// - w must be in {8,16,32}
+    /// - not supported for Liberation, Liber8tion, BlaumRoth
     Matrix,
     /// The bit-matrix coding technique.
     ///
@@ -44,9 +64,12 @@ pub enum Technique {
     Schedule,
     /// The schedule coding technique with cache.
     ///
+    /// For m = 2, the decoding schedules of all the erasure patterns are precomputed by Jerasure.
+    /// For other m, the decoding schedule of an erasure pattern is computed on its first
+    /// decoding, and cached for the later ones.
+    ///
     /// # Requires
     /// - same as `Schedule`
-    /// - m must be 2
     /// - not supported for ReedSolVand
     ScheduleCache,
 }
@@ -70,27 +93,48 @@ enum TechInner {
     /// - packet_size must be set
     /// - w not greater than 32
     Schedule(Schedule),
+    /// The schedule coding technique with cache.
+    ///
     /// # Requires
-    /// - m must be 2
+    /// - packet_size must be set
+    /// - w not greater than 32
     ScheduleCache(ScheduleCache),
 }
 
 #[derive(Debug)]
 struct Matrix {
     ptr: *mut c_int,
+    len: usize,
 }
 
 impl Matrix {
-    /// Make a malloc box from a pointer from `malloc`.
+    /// Make a malloc box from a pointer from `malloc`, which holds `len` elements.
     ///
     /// # Safety
     /// This function is unsafe because improper use may lead to memory problems. For example,
     /// a double-free may occur if the function is called twice on the same raw pointer.
-    unsafe fn try_from_raw(ptr: *mut c_int) -> Option<Self> {
+    unsafe fn try_from_raw(ptr: *mut c_int, len: usize) -> Option<Self> {
+        if ptr.is_null() {
+            return None;
+        }
+        Some(Self { ptr, len })
+    }
+
+    /// Copy the elements into a matrix allocated by `malloc`.
+    fn from_slice(elems: &[c_int]) -> Option<Self> {
+        let ptr = unsafe { malloc(std::mem::size_of_val(elems)) } as *mut c_int;
         if ptr.is_null() {
             return None;
         }
-        Some(Self { ptr })
+        unsafe { std::ptr::copy_nonoverlapping(elems.as_ptr(), ptr, elems.len()) };
+        Some(Self {
+            ptr,
+            len: elems.len(),
+        })
+    }
+
+    fn as_slice(&self) -> &[c_int] {
+        unsafe { std::slice::from_raw_parts(self.ptr, self.len) }
     }
 
     fn as_ptr(&self) -> *mut c_int {
@@ -127,22 +171,42 @@ impl Drop for Schedule {
 
 #[derive(Debug)]
 struct ScheduleCache {
+    bmat: Matrix,
     packet_size: i32,
     k: i32,
     m: i32,
     schedule: *mut *mut c_int,
+    /// The schedule cache generated by Jerasure for m = 2, or null for other m.
     cache: *mut *mut *mut c_int,
+    /// The decoding schedules computed so far for m other than 2, keyed by erasure pattern.
+    decoding: Mutex<HashMap<Vec<i32>, Arc<DecodingSchedule>>>,
 }
 
 impl Drop for ScheduleCache {
     fn drop(&mut self) {
         unsafe {
             jerasure_sys::jerasure::jerasure_free_schedule(self.schedule);
-            jerasure_sys::jerasure::jerasure_free_schedule_cache(self.k, self.m, self.cache);
+            if !self.cache.is_null() {
+                jerasure_sys::jerasure::jerasure_free_schedule_cache(self.k, self.m, self.cache);
+            }
         }
     }
 }
 
+/// The schedule recovering the erased devices of an erasure pattern from the `survivors`,
+/// where the survivors are the data devices and the erased devices are the coding devices
+/// of the schedule.
+#[derive(Debug)]
+struct DecodingSchedule {
+    survivors: Vec<i32>,
+    erased: Vec<i32>,
+    schedule: Schedule,
+}
+
+// SAFETY: the schedule is never mutated after it is computed.
+unsafe impl Send for DecodingSchedule {}
+unsafe impl Sync for DecodingSchedule {}
+
 #[derive(Debug, Clone, Copy)]
 /// The `CodingMethod` is used to represent the coding method used to encode and decode the data.
 ///
@@ -155,13 +219,65 @@ pub enum CodingMethod {
     /// - w must be in {8,16,32}
     /// - not supported for BitMatrix, Schedule, ScheduleCache
     ReedSolVand,
+    /// The Reed-Solomon coding method optimized for RAID-6.
+    ///
+    /// The first parity is the XOR of the data, and the second parity is the sum of
+    /// the data multiplied by the powers of 2, which allows a faster encoding.
+    ///
+    /// # Requires
+    /// - m must be 2
+    /// - w must be in {8,16,32}
+    /// - not supported for BitMatrix, Schedule, ScheduleCache
+    ReedSolR6,
     /// The Cauchy coding method.
     ///
     /// # Requires
     /// - packet_size must be set to a multiple of the machine long size
     Cauchy,
+    /// The original Cauchy coding method, without the optimization of
+    /// the number of ones in the bit-matrix applied by `Cauchy`.
+    ///
+    /// # Requires
+    /// - same as `Cauchy`
+    CauchyOrig,
+    /// The Cauchy coding method with the X and Y sets supplied by
+    /// [`ErasureCodeBuilder::cauchy_xy`].
+    ///
+    /// # Requires
+    /// - same as `Cauchy`
+    /// - the X and Y sets must be set
+    CauchyXY,
+    /// A user-supplied coding matrix, set by [`ErasureCodeBuilder::coding_matrix`],
+    /// or a user-supplied coding bit-matrix, set by [`ErasureCodeBuilder::coding_bitmatrix`].
+    ///
+    /// # Requires
+    /// - same as `Cauchy` for a coding matrix
+    /// - techniques other than `Matrix` for a coding bit-matrix
+    /// - the code must be MDS, i.e. any k out of the k + m devices can recover the data
+    Custom,
+    /// The Liberation coding method, a RAID-6 code defined directly by a bit-matrix.
+    ///
+    /// # Requires
+    /// - m must be 2
+    /// - w must be a prime number greater than 2
+    /// - k must not be greater than w
+    /// - only supported for BitMatrix, Schedule, ScheduleCache
     Liberation,
+    /// The Liber8tion coding method, a RAID-6 bit-matrix code specialized for w = 8.
+    ///
+    /// # Requires
+    /// - m must be 2
+    /// - w must be 8
+    /// - k must not be greater than 8
+    /// - only supported for BitMatrix, Schedule, ScheduleCache
     Liber8tion,
+    /// The Blaum-Roth coding method, a RAID-6 code defined directly by a bit-matrix.
+    ///
+    /// # Requires
+    /// - m must be 2
+    /// - w + 1 must be a prime number, and w must be greater than 2
+    /// - k must not be greater than w
+    /// - only supported for BitMatrix, Schedule, ScheduleCache
     BlaumRoth,
 }
 
@@ -176,6 +292,17 @@ pub struct ErasureCodeBuilder {
     packet_size: Option<i32>,
     tech: Option<Technique>,
     coding_method: Option<CodingMethod>,
+    cauchy_xy: Option<(Vec<i32>, Vec<i32>)>,
+    custom: Option<CustomMatrix>,
+    decoding_cache: Option<NonZeroUsize>,
+    galois_field: Option<GaloisField>,
+}
+
+/// The user-supplied coding matrix of `CodingMethod::Custom`.
+#[derive(Debug, Clone)]
+enum CustomMatrix {
+    Matrix(Vec<i32>),
+    BitMatrix(Vec<i32>),
 }
 
 impl ErasureCodeBuilder {
@@ -186,6 +313,10 @@ impl ErasureCodeBuilder {
     /// - `packet_size` is not set
     /// - `tech` is not set
     /// - `coding_method` is not set
+    /// - `cauchy_xy` is not set
+    /// - neither `coding_matrix` nor `coding_bitmatrix` is set
+    /// - the decoding cache is disabled
+    /// - the default Galois field of Jerasure is used
     pub fn new() -> Self {
         Self {
             ..Default::default()
@@ -237,8 +368,187 @@ impl ErasureCodeBuilder {
         self
     }
 
+    /// Set the X and Y sets of the Cauchy matrix, used by `CodingMethod::CauchyXY`.
+    ///
+    /// The element $(i, j)$ of the coding matrix is $1 / (x_i + y_j)$ in GF(2^w).
+    ///
+    /// # Requires
+    /// - `x` has `m` elements, and `y` has `k` elements
+    /// - all the elements of `x` and `y` are distinct, and in the range $[0, 2^w)$
+    pub fn cauchy_xy(mut self, x: impl Into<Vec<i32>>, y: impl Into<Vec<i32>>) -> Self {
+        self.cauchy_xy = Some((x.into(), y.into()));
+        self
+    }
+
+    /// Set the coding matrix used by `CodingMethod::Custom`, replacing the coding bit-matrix if any.
+    ///
+    /// The matrix has `m` rows and `k` columns in row-major order, and the element
+    /// $(i, j)$ is the coefficient of the data device $j$ in the coding device $i$,
+    /// which is the same layout as the matrices generated by Jerasure, ISA-L or HDFS
+    /// without the identity part.
+    ///
+    /// # Requires
+    /// - `matrix` has $m \times k$ elements, each in the range $[0, 2^w)$
+    /// - the code is MDS, i.e. every $k \times k$ submatrix of the generator matrix is invertible
+    pub fn coding_matrix(mut self, matrix: impl Into<Vec<i32>>) -> Self {
+        self.custom = Some(CustomMatrix::Matrix(matrix.into()));
+        self
+    }
+
+    /// Set the coding bit-matrix used by `CodingMethod::Custom`, replacing the coding matrix if any.
+    ///
+    /// The bit-matrix has $m \times w$ rows and $k \times w$ columns in row-major order.
+    ///
+    /// # Requires
+    /// - `bitmatrix` has $m \times k \times w^2$ elements, each being 0 or 1
+    /// - the code is MDS, i.e. every $kw \times kw$ submatrix of the generator bit-matrix
+    ///   is invertible
+    pub fn coding_bitmatrix(mut self, bitmatrix: impl Into<Vec<i32>>) -> Self {
+        self.custom = Some(CustomMatrix::BitMatrix(bitmatrix.into()));
+        self
+    }
+
+    /// Enable the cache of decoding matrices, holding up to `capacity` erasure patterns.
+    ///
+    /// With the cache enabled, [`ErasureCode::decode`] computes the decoding matrix of an
+    /// erasure pattern once, and reuses it until it is evicted as the least recently used one,
+    /// for all the techniques and all `m`.
+    /// The hits and misses are reported by [`ErasureCode::decoding_cache_stats`].
+    ///
+    /// # Default
+    /// - disabled
+    pub fn decoding_cache(mut self, capacity: NonZeroUsize) -> Self {
+        self.decoding_cache = Some(capacity);
+        self
+    }
+
+    /// Set the Galois field that the coding matrix is generated and applied in,
+    /// and the code word size to the one of the field.
+    ///
+    /// With a field built by [`GaloisFieldBuilder`](crate::galois::GaloisFieldBuilder),
+    /// e.g. with the primitive polynomial of another implementation, the coding matrices
+    /// are generated by the same algorithms as Jerasure but in that field, so they match
+    /// the ones of the other implementation, e.g. `CauchyXY` with $X = \{k, ..., k+m-1\}$
+    /// and $Y = \{0, ..., k-1\}$ is the Cauchy matrix of ISA-L in the field of `0x11d`.
+    /// `Cauchy` always improves the original Cauchy matrix in such a field, since the best
+    /// RAID-6 matrices of Jerasure are only known for its default fields.
+    ///
+    /// A composite field of [`MultType::Composite`](crate::galois::MultType::Composite),
+    /// e.g. GF((2^16)^2), gives faster multiplication for `w = 32` with `Technique::Matrix`,
+    /// but it is another field than the default one, so the parities differ from the ones
+    /// of the default field.
+    ///
+    /// # Default
+    /// - the default Galois field of Jerasure for `w`
+    ///
+    /// # Requires
+    /// - the field stores the elements of the regions in the standard layout,
+    ///   i.e. it is not created with `RegionType::ALTMAP` or `RegionType::CAUCHY`
+    /// - the coding method is not defined by a bit-matrix, i.e. not `Liberation`,
+    ///   `Liber8tion`, `BlaumRoth`, or `Custom` with a coding bit-matrix
+    pub fn galois_field(mut self, gf: GaloisField) -> Self {
+        self.w = gf.get_w();
+        self.galois_field = Some(gf);
+        self
+    }
+
     /// Build the `ErasureCode` struct.
+    ///
+    /// All the parameters are validated before any coding matrix is generated,
+    /// so an invalid combination is reported as an [`Error`] instead of aborting
+    /// inside Jerasure.
+    ///
+    /// # Compatibility
+    /// `k`, `m`, `tech` and `coding_method` are required, `k` and `m` must be
+    /// greater than 0, w must be in 1..=32 and $k + m <= 2^w$. The $k \times k$
+    /// decoding matrix, which has $w \times w$ bits per element for the techniques
+    /// other than `Matrix`, must have at most `i32::MAX` elements, as must the coding matrix.
+    /// Besides, the following combinations are supported:
+    ///
+    /// | coding method | technique                                | w                           | k        | m |
+    /// |---------------|------------------------------------------|-----------------------------|----------|---|
+    /// | `ReedSolVand` | `Matrix`                                 | {8,16,32}                   |          |   |
+    /// | `ReedSolR6`   | `Matrix`                                 | {8,16,32}                   |          | 2 |
+    /// | `Cauchy`      | `Matrix`                                 | {8,16,32}                   |          |   |
+    /// | `Cauchy`      | `BitMatrix`, `Schedule`, `ScheduleCache` | any                         |          |   |
+    /// | `Liberation`  | `BitMatrix`, `Schedule`, `ScheduleCache` | prime, greater than 2       | $k <= w$ | 2 |
+    /// | `Liber8tion`  | `BitMatrix`, `Schedule`, `ScheduleCache` | 8                           | $k <= 8$ | 2 |
+    /// | `BlaumRoth`   | `BitMatrix`, `Schedule`, `ScheduleCache` | w + 1 prime, greater than 2 | $k <= w$ | 2 |
+    ///
+    /// `CauchyOrig` and `CauchyXY` follow the same rules as `Cauchy`, and `CauchyXY`
+    /// additionally requires the X and Y sets set by [`ErasureCodeBuilder::cauchy_xy`].
+    /// `Custom` follows the same rules as `Cauchy` with a coding matrix, and supports
+    /// all the techniques but `Matrix` with a coding bit-matrix. The supplied matrix
+    /// must be MDS, which is checked against all the $\binom{k+m}{m}$ erasure patterns,
+    /// so there must be at most 65536 of them, e.g. $k + m <= 18$, or $m = 3$ and $k <= 71$.
+    ///
+    /// The `packet_size` is ignored by the `Matrix` technique, and is required to be
+    /// a positive multiple of the machine long size by the other techniques.
+    ///
+    /// A Galois field set by [`ErasureCodeBuilder::galois_field`] must have the word size `w`,
+    /// and is not supported by the coding methods defined by a bit-matrix.
+    ///
+    /// # Errors
+    /// - [`Error::InvalidArguments`] if a parameter is missing or out of range,
+    ///   or the packet size is invalid.
+    /// - [`Error::NotSupported`] if the combination is not listed above.
     pub fn build(self) -> Result<ErasureCode, Error> {
+        let (k, m, tech, coding_method) = self.check_compatibility()?;
+        let w = self.w;
+        // the coding matrix in GF(2^w), if the coding method is not bit-matrix only
+        let mat = match (coding_method, &self.custom) {
+            (CodingMethod::ReedSolVand, _) => Some(self.reed_sol_vand_mat(k, m)?),
+            (CodingMethod::ReedSolR6, _) => Some(self.reed_sol_r6_mat(k)?),
+            (CodingMethod::Cauchy | CodingMethod::CauchyOrig | CodingMethod::CauchyXY, _) => {
+                Some(self.cauchy_mat(k, m)?)
+            }
+            (CodingMethod::Custom, Some(CustomMatrix::Matrix(mat))) => Some(
+                Matrix::from_slice(mat)
+                    .ok_or_else(|| Error::other("Failed to allocate coding matrix"))?,
+            ),
+            _ => None,
+        };
+        let (tech, mat) = match (tech, mat) {
+            (Technique::Matrix, Some(mat)) => (TechInner::Matrix(mat), None),
+            (_, Some(mut mat)) => {
+                let bmat = self.mat_to_bitmat(k, m, &mut mat)?;
+                (self.bmat_to_tech(k, m, tech, bmat)?, Some(mat))
+            }
+            (_, None) => {
+                let bmat = match (coding_method, &self.custom) {
+                    (CodingMethod::Liberation, _) => self.liberation_bitmat(k)?,
+                    (CodingMethod::Liber8tion, _) => self.liber8tion_bitmat(k)?,
+                    (CodingMethod::BlaumRoth, _) => self.blaum_roth_bitmat(k)?,
+                    (CodingMethod::Custom, Some(CustomMatrix::BitMatrix(bmat))) => {
+                        Matrix::from_slice(bmat)
+                            .ok_or_else(|| Error::other("Failed to allocate coding bit-matrix"))?
+                    }
+                    _ => unreachable!("checked by check_compatibility"),
+                };
+                (self.bmat_to_tech(k, m, tech, bmat)?, None)
+            }
+        };
+
+        let mut ec = ErasureCode {
+            tech,
+            mat,
+            k,
+            m,
+            w,
+            method: coding_method,
+            cache: self.decoding_cache.map(DecodingCache::new),
+            gf: self.custom_field().cloned(),
+            fingerprint: 0,
+        };
+        ec.fingerprint = ec.coding_fingerprint();
+        Ok(ec)
+    }
+}
+
+impl ErasureCodeBuilder {
+    /// Check the parameters against the compatibility table documented in [`build`](Self::build),
+    /// and return the validated `k`, `m`, technique and coding method.
+    fn check_compatibility(&self) -> Result<(i32, i32, Technique, CodingMethod), Error> {
         let k: i32 = self
             .k
             .ok_or_else(|| Error::invalid_arguments("k is required"))?;
@@ -248,7 +558,6 @@ impl ErasureCodeBuilder {
         let tech = self
             .tech
             .ok_or_else(|| Error::invalid_arguments("tech is required"))?;
-        let w = self.w;
         let coding_method = self
             .coding_method
             .ok_or_else(|| Error::invalid_arguments("coding_method is required"))?;
@@ -258,127 +567,480 @@ impl ErasureCodeBuilder {
         if m <= 0 {
             return Err(Error::invalid_arguments("m must be greater than 0"));
         }
-        if k + m > (1 << w.to_u8()) {
+        let w = i32::from(self.w.to_u8());
+        if !(1..=32).contains(&w) {
+            return Err(Error::not_supported(format!("w({w}) must be in 1..=32")));
+        }
+        if i64::from(k) + i64::from(m) > 1_i64 << w {
             return Err(Error::invalid_arguments(format!(
                 "k + m must be less or equal than 2^w({})",
-                1 << w.to_u8()
+                1_i64 << w
             )));
         }
-        let mat = match coding_method {
-            CodingMethod::ReedSolVand => self.reed_sol_vand_mat()?,
-            CodingMethod::Cauchy => self.cauchy_mat()?,
-            _ => unimplemented!("Liber8tion, BlaumRoth are not implemented yet"),
+        // Jerasure computes the number of elements of its matrices in `int`, up to the
+        // k x k decoding matrix, which has w x w bits per element for the bit-matrix techniques
+        let bits = if matches!(tech, Technique::Matrix) {
+            1
+        } else {
+            i128::from(w) * i128::from(w)
         };
+        let elems = i128::from(k) * i128::from(k.max(m)) * bits;
+        if elems > i128::from(c_int::MAX) {
+            return Err(Error::invalid_arguments(format!(
+                "k({k}) and m({m}) are too large for w({w}) and {tech:?}: \
+                 the matrices would have {elems} elements"
+            )));
+        }
+        if let Some(gf) = &self.galois_field {
+            let gf_w = i32::from(gf.get_w().to_u8());
+            if gf_w != w {
+                return Err(Error::invalid_arguments(format!(
+                    "w({gf_w}) of the galois field must be w({w})"
+                )));
+            }
+            if !gf.has_standard_regions() {
+                return Err(Error::not_supported(
+                    "the galois field must store the regions in the standard layout",
+                ));
+            }
+        }
+        if self.custom_field().is_some()
+            && (matches!(
+                coding_method,
+                CodingMethod::Liberation | CodingMethod::Liber8tion | CodingMethod::BlaumRoth
+            ) || matches!(self.custom, Some(CustomMatrix::BitMatrix(_))))
+        {
+            return Err(Error::not_supported(format!(
+                "a galois field is not supported for {coding_method:?} defined by a bit-matrix"
+            )));
+        }
 
-        let tech = match tech {
-            Technique::Matrix => {
-                // w must be in {8,16,32}
-                if matches!(w, CodeWord::Other(_)) {
+        match (coding_method, tech) {
+            (
+                CodingMethod::ReedSolVand
+                | CodingMethod::Cauchy
+                | CodingMethod::CauchyOrig
+                | CodingMethod::CauchyXY,
+                Technique::Matrix,
+            ) => {
+                if !matches!(w, 8 | 16 | 32) {
+                    return Err(Error::not_supported("w must be in {8,16,32}"));
+                }
+            }
+            (CodingMethod::ReedSolR6, Technique::Matrix) => {
+                if !matches!(w, 8 | 16 | 32) {
                     return Err(Error::not_supported("w must be in {8,16,32}"));
                 }
-                TechInner::Matrix(mat)
+                if m != 2 {
+                    return Err(Error::not_supported(
+                        "ReedSolR6 is only supported for m = 2",
+                    ));
+                }
             }
-            Technique::BitMatrix => {
-                if matches!(coding_method, CodingMethod::ReedSolVand) {
+            (CodingMethod::ReedSolVand | CodingMethod::ReedSolR6, _) => {
+                return Err(Error::not_supported(format!(
+                    "{tech:?} is not supported for {coding_method:?}"
+                )));
+            }
+            (CodingMethod::Cauchy | CodingMethod::CauchyOrig | CodingMethod::CauchyXY, _) => {}
+            (CodingMethod::Custom, tech) => match (&self.custom, tech) {
+                (None, _) => {
+                    return Err(Error::invalid_arguments(
+                        "coding_matrix or coding_bitmatrix is required for Custom",
+                    ));
+                }
+                (Some(CustomMatrix::Matrix(_)), Technique::Matrix) => {
+                    if !matches!(w, 8 | 16 | 32) {
+                        return Err(Error::not_supported("w must be in {8,16,32}"));
+                    }
+                }
+                (Some(CustomMatrix::BitMatrix(_)), Technique::Matrix) => {
                     return Err(Error::not_supported(
-                        "BitMatrix is not supported for ReedSolVand",
+                        "Matrix is not supported for a coding bit-matrix",
                     ));
                 }
-                let bmat = self.mat_to_bitmat(mat)?;
-                TechInner::BitMatrix(bmat, self.check_packet_size()?)
+                (Some(_), _) => {}
+            },
+            (_, Technique::Matrix) => {
+                return Err(Error::not_supported(format!(
+                    "Matrix is not supported for {coding_method:?}"
+                )));
             }
-            Technique::Schedule => {
-                if matches!(coding_method, CodingMethod::ReedSolVand) {
+            (CodingMethod::Liberation, _) => {
+                if m != 2 {
                     return Err(Error::not_supported(
-                        "Schedule is not supported for ReedSolVand",
+                        "Liberation is only supported for m = 2",
                     ));
                 }
-                let bmat = self.mat_to_bitmat(mat)?;
-                let schedule = self.bmat_to_schedule(bmat)?;
-                TechInner::Schedule(schedule)
+                if w <= 2 || !is_prime(w) {
+                    return Err(Error::not_supported(format!(
+                        "Liberation requires w({w}) to be a prime number greater than 2"
+                    )));
+                }
+                if k > w {
+                    return Err(Error::not_supported(format!(
+                        "Liberation requires k({k}) not greater than w({w})"
+                    )));
+                }
             }
-            Technique::ScheduleCache => {
-                if matches!(coding_method, CodingMethod::ReedSolVand) {
+            (CodingMethod::Liber8tion, _) => {
+                if m != 2 {
                     return Err(Error::not_supported(
-                        "ScheduleCache is not supported for ReedSolVand",
+                        "Liber8tion is only supported for m = 2",
                     ));
                 }
+                if w != 8 {
+                    return Err(Error::not_supported(format!(
+                        "Liber8tion requires w({w}) to be 8"
+                    )));
+                }
+                if k > 8 {
+                    return Err(Error::not_supported(format!(
+                        "Liber8tion requires k({k}) not greater than 8"
+                    )));
+                }
+            }
+            (CodingMethod::BlaumRoth, _) => {
                 if m != 2 {
                     return Err(Error::not_supported(
-                        "ScheduleCache is only supported for m = 2",
+                        "BlaumRoth is only supported for m = 2",
                     ));
                 }
-                let bmat = self.mat_to_bitmat(mat)?;
-                let schedule = self.bmat_toschedule_cache(bmat)?;
-                TechInner::ScheduleCache(schedule)
+                if w <= 2 || !is_prime(w + 1) {
+                    return Err(Error::not_supported(format!(
+                        "BlaumRoth requires w({w}) greater than 2 and w + 1 to be a prime number"
+                    )));
+                }
+                if k > w {
+                    return Err(Error::not_supported(format!(
+                        "BlaumRoth requires k({k}) not greater than w({w})"
+                    )));
+                }
+            }
+        }
+
+        match tech {
+            Technique::Matrix => {}
+            Technique::BitMatrix | Technique::Schedule | Technique::ScheduleCache => {
+                self.check_packet_size()?;
+            }
+        }
+
+        match coding_method {
+            CodingMethod::CauchyXY => self.check_cauchy_xy(k, m)?,
+            CodingMethod::Custom => self.check_custom(k, m)?,
+            _ => {}
+        }
+
+        Ok((k, m, tech, coding_method))
+    }
+
+    fn check_cauchy_xy(&self, k: i32, m: i32) -> Result<(), Error> {
+        let (x, y) = self
+            .cauchy_xy
+            .as_ref()
+            .ok_or_else(|| Error::invalid_arguments("cauchy_xy is required for CauchyXY"))?;
+        if x.len() != m as usize {
+            return Err(Error::invalid_arguments(format!(
+                "x must have m({m}) elements, got {}",
+                x.len()
+            )));
+        }
+        if y.len() != k as usize {
+            return Err(Error::invalid_arguments(format!(
+                "y must have k({k}) elements, got {}",
+                y.len()
+            )));
+        }
+        let bound = 1_i64 << self.w.to_u8();
+        if let Some(e) = x
+            .iter()
+            .chain(y.iter())
+            .find(|&&e| !(0..bound).contains(&i64::from(e as u32)))
+        {
+            return Err(Error::invalid_arguments(format!(
+                "element({}) of x and y must be in the range [0, 2^w({bound}))",
+                *e as u32
+            )));
+        }
+        if x.iter().chain(y.iter()).all_unique() {
+            Ok(())
+        } else {
+            Err(Error::invalid_arguments(
+                "elements of x and y must be distinct",
+            ))
+        }
+    }
+
+    /// Check the shape and the elements of the user-supplied coding matrix, and that the code is MDS.
+    ///
+    /// With the systematic generator matrix $[I; C]$, a $k \times k$ submatrix with the data rows
+    /// of the survivors and the parity rows $R$ is invertible iff the square submatrix of $C$
+    /// with the rows $R$ and the columns of the erased data is invertible,
+    /// so all the square submatrices of $C$ are checked.
+    fn check_custom(&self, k: i32, m: i32) -> Result<(), Error> {
+        let w = i32::from(self.w.to_u8());
+        let (elems, is_bitmatrix) = match &self.custom {
+            Some(CustomMatrix::Matrix(mat)) => (mat, false),
+            Some(CustomMatrix::BitMatrix(bmat)) => (bmat, true),
+            None => {
+                return Err(Error::invalid_arguments(
+                    "coding_matrix or coding_bitmatrix is required for Custom",
+                ));
             }
         };
+        // the size of each coefficient block
+        let b = if is_bitmatrix { w as usize } else { 1 };
+        let (k, m) = (k as usize, m as usize);
+        if elems.len() != m * k * b * b {
+            return Err(Error::invalid_arguments(format!(
+                "the coding {} must have {} elements, got {}",
+                if is_bitmatrix { "bit-matrix" } else { "matrix" },
+                m * k * b * b,
+                elems.len()
+            )));
+        }
+        let bound = if is_bitmatrix { 2 } else { 1_i64 << w };
+        if let Some(pos) = elems
+            .iter()
+            .position(|&e| !(0..bound).contains(&i64::from(e as u32)))
+        {
+            return Err(Error::invalid_arguments(format!(
+                "element({}) at index {pos} of the coding {} must be in the range [0, {bound})",
+                elems[pos] as u32,
+                if is_bitmatrix { "bit-matrix" } else { "matrix" },
+            )));
+        }
 
-        Ok(ErasureCode {
-            tech,
-            k,
-            m,
-            w,
-            method: coding_method,
-        })
+        // C(k + i, i) = C(k + i - 1, i - 1) * (k + i) / i, which stops growing past the limit
+        let patterns = (1..=m as u64).try_fold(1_u64, |n, i| {
+            Some(n * (k as u64 + i) / i).filter(|&n| n <= MAX_CUSTOM_PATTERNS)
+        });
+        if patterns.is_none() {
+            return Err(Error::not_supported(format!(
+                "the MDS check of Custom covers at most {MAX_CUSTOM_PATTERNS} erasure patterns, \
+                 but k({k}) and m({m}) have more"
+            )));
+        }
+
+        let cols = k * b;
+        for s in 1..=k.min(m) {
+            let n = s * b;
+            for rows in (0..m).combinations(s) {
+                for erased in (0..k).combinations(s) {
+                    let mut sub = Vec::with_capacity(n * n);
+                    for &row in &rows {
+                        for r in row * b..(row + 1) * b {
+                            for &col in &erased {
+                                sub.extend_from_slice(&elems[r * cols + col * b..][..b]);
+                            }
+                        }
+                    }
+                    if let (Some(gf), false) = (self.custom_field(), is_bitmatrix) {
+                        if !crate::matrix::Matrix::new(gf.clone(), n, n, sub)?.is_invertible() {
+                            return Err(Error::invalid_arguments(format!(
+                                "the coding matrix is not MDS: data devices {erased:?} cannot be \
+                                 recovered from coding devices {rows:?}, since the submatrix of \
+                                 these rows and columns is singular"
+                            )));
+                        }
+                        continue;
+                    }
+                    let invertible = unsafe {
+                        if is_bitmatrix {
+                            jerasure_sys::jerasure::jerasure_invertible_bitmatrix(
+                                sub.as_mut_ptr(),
+                                n as c_int,
+                            )
+                        } else {
+                            jerasure_sys::jerasure::jerasure_invertible_matrix(
+                                sub.as_mut_ptr(),
+                                n as c_int,
+                                w,
+                            )
+                        }
+                    };
+                    if invertible == 0 {
+                        return Err(Error::invalid_arguments(format!(
+                            "the coding matrix is not MDS: data devices {erased:?} cannot be \
+                             recovered from coding devices {rows:?}, since the submatrix of \
+                             these rows and columns is singular"
+                        )));
+                    }
+                }
+            }
+        }
+        Ok(())
     }
-}
 
-impl ErasureCodeBuilder {
-    fn reed_sol_vand_mat(&self) -> Result<Matrix, Error> {
-        let k = self.k.unwrap();
-        let m = self.m.unwrap();
+    /// Return the Galois field set by [`galois_field`](Self::galois_field),
+    /// or `None` if it is the default field of Jerasure.
+    fn custom_field(&self) -> Option<&GaloisField> {
+        self.galois_field.as_ref().filter(|gf| !gf.is_default())
+    }
+
+    fn reed_sol_vand_mat(&self, k: i32, m: i32) -> Result<Matrix, Error> {
         let w = self.w;
+        if let Some(gf) = self.custom_field() {
+            return Matrix::from_slice(&vandermonde_coding_matrix(gf, k, m))
+                .ok_or_else(|| Error::other("Failed to create reed solomon vandermonde matrix"));
+        }
 
         unsafe {
-            Matrix::try_from_raw(jerasure_sys::jerasure::reed_sol_vandermonde_coding_matrix(
-                k,
-                m,
-                w.as_cint(),
-            ))
+            Matrix::try_from_raw(
+                jerasure_sys::jerasure::reed_sol_vandermonde_coding_matrix(k, m, w.as_cint()),
+                k as usize * m as usize,
+            )
         }
         .ok_or_else(|| Error::other("Failed to create reed solomon vandermonde matrix"))
     }
 
-    fn cauchy_mat(&self) -> Result<Matrix, Error> {
-        let k = self
-            .k
-            .ok_or_else(|| Error::invalid_arguments("k is required"))?;
-        let m = self
-            .m
-            .ok_or_else(|| Error::invalid_arguments("m is required"))?;
+    fn reed_sol_r6_mat(&self, k: i32) -> Result<Matrix, Error> {
         let w = self.w;
+        if let Some(gf) = self.custom_field() {
+            // the first row is all ones, and the second row is the powers of 2
+            let powers = (0..k).scan(1, |e, _| {
+                let power = *e;
+                *e = gf.multiply(*e, 2);
+                Some(power)
+            });
+            let mat = std::iter::repeat_n(1, k as usize)
+                .chain(powers)
+                .collect_vec();
+            return Matrix::from_slice(&mat)
+                .ok_or_else(|| Error::other("Failed to create reed solomon RAID-6 matrix"));
+        }
 
         unsafe {
-            Matrix::try_from_raw(jerasure_sys::jerasure::cauchy_good_general_coding_matrix(
-                k,
-                m,
-                w.as_cint(),
-            ))
+            Matrix::try_from_raw(
+                jerasure_sys::jerasure::reed_sol_r6_coding_matrix(k, w.as_cint()),
+                2 * k as usize,
+            )
+        }
+        .ok_or_else(|| Error::other("Failed to create reed solomon RAID-6 matrix"))
+    }
+
+    fn cauchy_mat(&self, k: i32, m: i32) -> Result<Matrix, Error> {
+        let w = self.w;
+        if let Some(gf) = self.custom_field() {
+            let mat = match (self.coding_method, &self.cauchy_xy) {
+                (Some(CodingMethod::CauchyXY), Some((x, y))) => cauchy_coding_matrix(gf, x, y),
+                (Some(CodingMethod::CauchyXY), None) => {
+                    return Err(Error::invalid_arguments("cauchy_xy is required"));
+                }
+                (Some(CodingMethod::CauchyOrig), _) => {
+                    cauchy_coding_matrix(gf, &(0..m).collect_vec(), &(m..m + k).collect_vec())
+                }
+                _ => {
+                    let mut mat =
+                        cauchy_coding_matrix(gf, &(0..m).collect_vec(), &(m..m + k).collect_vec());
+                    cauchy_improve_coding_matrix(gf, k, m, &mut mat);
+                    mat
+                }
+            };
+            return Matrix::from_slice(&mat)
+                .ok_or_else(|| Error::other("Failed to create cauchy matrix"));
         }
-        .ok_or_else(|| Error::other("Failed to create cauchy matrix"))
+
+        let ptr = unsafe {
+            match self.coding_method {
+                Some(CodingMethod::CauchyOrig) => {
+                    jerasure_sys::jerasure::cauchy_original_coding_matrix(k, m, w.as_cint())
+                }
+                Some(CodingMethod::CauchyXY) => {
+                    let (x, y) = self
+                        .cauchy_xy
+                        .as_ref()
+                        .ok_or_else(|| Error::invalid_arguments("cauchy_xy is required"))?;
+                    jerasure_sys::jerasure::cauchy_xy_coding_matrix(
+                        k,
+                        m,
+                        w.as_cint(),
+                        x.as_ptr() as *mut c_int,
+                        y.as_ptr() as *mut c_int,
+                    )
+                }
+                _ => jerasure_sys::jerasure::cauchy_good_general_coding_matrix(k, m, w.as_cint()),
+            }
+        };
+        unsafe { Matrix::try_from_raw(ptr, k as usize * m as usize) }
+            .ok_or_else(|| Error::other("Failed to create cauchy matrix"))
     }
 
-    fn mat_to_bitmat(&self, mut mat: Matrix) -> Result<Matrix, Error> {
-        let k = self.k.unwrap();
-        let m = self.m.unwrap();
+    fn mat_to_bitmat(&self, k: i32, m: i32, mat: &mut Matrix) -> Result<Matrix, Error> {
         let w = self.w;
+        if let Some(gf) = self.custom_field() {
+            let mat =
+                crate::matrix::Matrix::new(gf.clone(), m as usize, k as usize, mat.as_slice())?;
+            return Matrix::from_slice(crate::matrix::BitMatrix::from(&mat).as_slice())
+                .ok_or_else(|| Error::other("Failed to create bit matrix"));
+        }
 
         unsafe {
-            Matrix::try_from_raw(jerasure_sys::jerasure::jerasure_matrix_to_bitmatrix(
-                k,
-                m,
-                w.as_cint(),
-                mat.as_mut_ptr(),
-            ))
+            Matrix::try_from_raw(
+                jerasure_sys::jerasure::jerasure_matrix_to_bitmatrix(
+                    k,
+                    m,
+                    w.as_cint(),
+                    mat.as_mut_ptr(),
+                ),
+                k as usize * m as usize * usize::from(w.to_u8()).pow(2),
+            )
         }
         .ok_or_else(|| Error::other("Failed to create bit matrix"))
     }
 
-    fn bmat_to_schedule(&self, mut bmat: Matrix) -> Result<Schedule, Error> {
-        let k = self.k.unwrap();
-        let m = self.m.unwrap();
+    fn liberation_bitmat(&self, k: i32) -> Result<Matrix, Error> {
+        let w = self.w;
+
+        unsafe {
+            Matrix::try_from_raw(
+                liberation_coding_bitmatrix(k, w.as_cint()),
+                2 * k as usize * usize::from(w.to_u8()).pow(2),
+            )
+        }
+        .ok_or_else(|| Error::other("Failed to create liberation bit matrix"))
+    }
+
+    fn liber8tion_bitmat(&self, k: i32) -> Result<Matrix, Error> {
+        unsafe { Matrix::try_from_raw(liber8tion_coding_bitmatrix(k), 2 * k as usize * 8 * 8) }
+            .ok_or_else(|| Error::other("Failed to create liber8tion bit matrix"))
+    }
+
+    fn blaum_roth_bitmat(&self, k: i32) -> Result<Matrix, Error> {
+        let w = self.w;
+
+        unsafe {
+            Matrix::try_from_raw(
+                blaum_roth_coding_bitmatrix(k, w.as_cint()),
+                2 * k as usize * usize::from(w.to_u8()).pow(2),
+            )
+        }
+        .ok_or_else(|| Error::other("Failed to create blaum-roth bit matrix"))
+    }
+
+    fn bmat_to_tech(
+        &self,
+        k: i32,
+        m: i32,
+        tech: Technique,
+        bmat: Matrix,
+    ) -> Result<TechInner, Error> {
+        match tech {
+            Technique::Matrix => Err(Error::not_supported(
+                "Matrix is not supported for bit-matrix coding",
+            )),
+            Technique::BitMatrix => Ok(TechInner::BitMatrix(bmat, self.check_packet_size()?)),
+            Technique::Schedule => Ok(TechInner::Schedule(self.bmat_to_schedule(k, m, bmat)?)),
+            Technique::ScheduleCache => Ok(TechInner::ScheduleCache(
+                self.bmat_toschedule_cache(k, m, bmat)?,
+            )),
+        }
+    }
+
+    fn bmat_to_schedule(&self, k: i32, m: i32, mut bmat: Matrix) -> Result<Schedule, Error> {
         let w = self.w;
+        let packet_size = self.check_packet_size()?;
 
         let p = unsafe {
             jerasure_sys::jerasure::jerasure_smart_bitmatrix_to_schedule(
@@ -393,16 +1055,20 @@ impl ErasureCodeBuilder {
         } else {
             Ok(Schedule {
                 bmat,
-                packet_size: self.check_packet_size()?,
+                packet_size,
                 inner: p,
             })
         }
     }
 
-    fn bmat_toschedule_cache(&self, mut bmat: Matrix) -> Result<ScheduleCache, Error> {
-        let k = self.k.unwrap();
-        let m = self.m.unwrap();
+    fn bmat_toschedule_cache(
+        &self,
+        k: i32,
+        m: i32,
+        mut bmat: Matrix,
+    ) -> Result<ScheduleCache, Error> {
         let w = self.w;
+        let packet_size = self.check_packet_size()?;
 
         let schedule = unsafe {
             jerasure_sys::jerasure::jerasure_smart_bitmatrix_to_schedule(
@@ -415,39 +1081,47 @@ impl ErasureCodeBuilder {
         if schedule.is_null() {
             return Err(Error::other("Failed to create schedule"));
         }
-        let cache = unsafe {
-            jerasure_sys::jerasure::jerasure_generate_schedule_cache(
-                k,
-                m,
-                w.as_cint(),
-                bmat.as_mut_ptr(),
-                1,
-            )
+        // Jerasure only generates the schedule cache for m = 2,
+        // the decoding schedules of other m are cached lazily by `decode`
+        let cache = if m == 2 {
+            let cache = unsafe {
+                jerasure_sys::jerasure::jerasure_generate_schedule_cache(
+                    k,
+                    m,
+                    w.as_cint(),
+                    bmat.as_mut_ptr(),
+                    1,
+                )
+            };
+            if cache.is_null() {
+                unsafe { jerasure_sys::jerasure::jerasure_free_schedule(schedule) };
+                return Err(Error::other("Failed to create schedule cache"));
+            }
+            cache
+        } else {
+            std::ptr::null_mut()
         };
-        if cache.is_null() {
-            unsafe { jerasure_sys::jerasure::jerasure_free_schedule(schedule) };
-            return Err(Error::other("Failed to create schedule cache"));
-        }
         Ok(ScheduleCache {
-            packet_size: self.check_packet_size()?,
+            bmat,
+            packet_size,
             schedule,
             cache,
+            decoding: Mutex::default(),
             k,
             m,
         })
     }
 
     fn check_packet_size(&self) -> Result<i32, Error> {
-        if self.packet_size.is_none() {
-            return Err(Error::invalid_arguments("packet_size is required"));
-        }
-        let packet_size = self.packet_size.unwrap();
+        let packet_size = self
+            .packet_size
+            .ok_or_else(|| Error::invalid_arguments("packet_size is required"))?;
         if packet_size <= 0 {
             return Err(Error::invalid_arguments(
                 "packet_size must be greater than 0",
             ));
         }
-        if packet_size % i32::try_from(crate::MACHINE_LONG_SIZE).unwrap() != 0 {
+        if packet_size % crate::MACHINE_LONG_SIZE as i32 != 0 {
             return Err(Error::invalid_arguments(format!(
                 "packet_size({packet_size}) must be a multiple of the machine long size({})",
                 crate::MACHINE_LONG_SIZE as i32
@@ -458,6 +1132,111 @@ impl ErasureCodeBuilder {
     }
 }
 
+fn is_prime(n: i32) -> bool {
+    n >= 2 && (2..).take_while(|i| i * i <= n).all(|i| n % i != 0)
+}
+
+/// Return the Reed-Solomon coding matrix generated in `gf` as `reed_sol_vandermonde_coding_matrix`,
+/// which is the last `m` rows of the extended Vandermonde matrix with `k + m` rows,
+/// transformed by column operations so that its first `k` rows are the identity,
+/// its row `k` is all ones, and the first element of the following rows is one.
+fn vandermonde_coding_matrix(gf: &GaloisField, k: i32, m: i32) -> Vec<i32> {
+    let (rows, cols) = ((k + m) as usize, k as usize);
+    let mut dist = vec![0; rows * cols];
+    dist[0] = 1;
+    dist[(rows - 1) * cols + cols - 1] = 1;
+    for i in 1..rows - 1 {
+        let mut e = 1;
+        for j in 0..cols {
+            dist[i * cols + j] = e;
+            e = gf.multiply(e, i as i32);
+        }
+    }
+    for i in 1..cols {
+        // swap a row with a non-zero element in column i into row i
+        let pivot = (i..rows).find(|&j| dist[j * cols + i] != 0).unwrap();
+        if pivot != i {
+            for c in 0..cols {
+                dist.swap(i * cols + c, pivot * cols + c);
+            }
+        }
+        let e = dist[i * cols + i];
+        if e != 1 {
+            let inv = gf.divide(1, e);
+            (0..rows).for_each(|r| dist[r * cols + i] = gf.multiply(inv, dist[r * cols + i]));
+        }
+        // zero the other elements of row i by adding multiples of column i
+        for j in (0..cols).filter(|&j| j != i) {
+            let e = dist[i * cols + j];
+            if e != 0 {
+                for r in 0..rows {
+                    let prod = gf.multiply(e, dist[r * cols + i]);
+                    dist[r * cols + j] ^= prod;
+                }
+            }
+        }
+    }
+    // make row k all ones by scaling the columns of the coding rows
+    for j in 0..cols {
+        let e = dist[cols * cols + j];
+        if e != 1 {
+            let inv = gf.divide(1, e);
+            (cols..rows).for_each(|r| dist[r * cols + j] = gf.multiply(inv, dist[r * cols + j]));
+        }
+    }
+    // make the first element of the following rows one by scaling the rows
+    for i in cols + 1..rows {
+        let e = dist[i * cols];
+        if e != 1 {
+            let inv = gf.divide(1, e);
+            (0..cols).for_each(|j| dist[i * cols + j] = gf.multiply(dist[i * cols + j], inv));
+        }
+    }
+    dist.split_off(cols * cols)
+}
+
+/// Return the Cauchy matrix generated in `gf` as `cauchy_xy_coding_matrix`,
+/// whose element $(i, j)$ is $1 / (x_i + y_j)$.
+fn cauchy_coding_matrix(gf: &GaloisField, x: &[i32], y: &[i32]) -> Vec<i32> {
+    x.iter()
+        .cartesian_product(y)
+        .map(|(&xi, &yj)| gf.divide(1, xi ^ yj))
+        .collect()
+}
+
+/// Reduce the number of ones in the bit-matrix of the Cauchy matrix `mat` in `gf`
+/// as `cauchy_improve_coding_matrix`, by scaling the columns so that the first row is all ones,
+/// then scaling each other row by the inverse of the element that reduces the most ones.
+fn cauchy_improve_coding_matrix(gf: &GaloisField, k: i32, m: i32, mat: &mut [i32]) {
+    let (k, m) = (k as usize, m as usize);
+    let n_ones = |e: i32| {
+        (0..gf.get_w().to_u8())
+            .map(|c| gf.multiply(e, 1 << c).count_ones())
+            .sum::<u32>()
+    };
+    for j in 0..k {
+        if mat[j] != 1 {
+            let inv = gf.divide(1, mat[j]);
+            (0..m).for_each(|i| mat[i * k + j] = gf.multiply(mat[i * k + j], inv));
+        }
+    }
+    for row in mat.chunks_mut(k).skip(1) {
+        let mut best = row.iter().map(|&e| n_ones(e)).sum::<u32>();
+        let mut best_inv = None;
+        for &e in row.iter().filter(|&&e| e != 1) {
+            let inv = gf.divide(1, e);
+            let ones = row.iter().map(|&e| n_ones(gf.multiply(e, inv))).sum();
+            if ones < best {
+                best = ones;
+                best_inv = Some(inv);
+            }
+        }
+        if let Some(inv) = best_inv {
+            row.iter_mut().for_each(|e| *e = gf.multiply(*e, inv));
+        }
+    }
+}
+
 /// The `ErasureCode` struct is used to encode and decode data using erasure codes.
 ///
 /// It is a wrapper around the Jerasure library, which provides efficient implementations
@@ -467,7 +1246,108 @@ pub struct ErasureCode {
     m: i32,
     w: CodeWord,
     tech: TechInner,
+    /// The coding matrix that the bit-matrix of `tech` is converted from.
+    mat: Option<Matrix>,
     method: CodingMethod,
+    cache: Option<DecodingCache>,
+    /// The Galois field set by [`ErasureCodeBuilder::galois_field`],
+    /// or `None` for the default field of Jerasure.
+    gf: Option<GaloisField>,
+    /// The hash of the coding matrix, the coding bit-matrix and the field,
+    /// which tells the decoding matrices and repair plans of other codes apart.
+    fingerprint: u64,
+}
+
+// SAFETY: the matrices and schedules are never mutated after the code is built.
+// Jerasure keeps its default Galois fields in thread-local storage, and a field set by
+// `ErasureCodeBuilder::galois_field` is `Send` and `Sync` by itself, as it gives each
+// operation a gf-complete instance with its own scratch memory,
+// so the code can be shared and sent between threads.
+unsafe impl Send for ErasureCode {}
+unsafe impl Sync for ErasureCode {}
+
+/// The statistics of the decoding cache, returned by [`ErasureCode::decoding_cache_stats`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecodingCacheStats {
+    /// The number of decodings that reused a cached decoding matrix.
+    pub hits: u64,
+    /// The number of decodings that computed a decoding matrix.
+    pub misses: u64,
+    /// The number of cached erasure patterns.
+    pub len: usize,
+    /// The maximum number of cached erasure patterns.
+    pub capacity: usize,
+}
+
+/// A bounded LRU cache of decoding matrices keyed by erasure pattern.
+#[derive(Debug)]
+struct DecodingCache {
+    capacity: NonZeroUsize,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    inner: Mutex<DecodingCacheInner>,
+}
+
+#[derive(Debug, Default)]
+struct DecodingCacheInner {
+    map: HashMap<Vec<i32>, Arc<DecodingMatrix>>,
+    /// The erasure patterns from the least to the most recently used.
+    order: VecDeque<Vec<i32>>,
+}
+
+impl DecodingCache {
+    fn new(capacity: NonZeroUsize) -> Self {
+        Self {
+            capacity,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            inner: Mutex::default(),
+        }
+    }
+
+    /// Return the cached decoding matrix of the sorted erasure pattern `erased`,
+    /// or compute it with `compute` and insert it.
+    fn get_or_try_insert(
+        &self,
+        erased: &[i32],
+        compute: impl FnOnce() -> Result<DecodingMatrix, Error>,
+    ) -> Result<Arc<DecodingMatrix>, Error> {
+        {
+            let mut guard = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+            let DecodingCacheInner { map, order } = &mut *guard;
+            if let Some(dm) = map.get(erased) {
+                let dm = Arc::clone(dm);
+                let pos = order.iter().position(|e| e == erased).unwrap();
+                let key = order.remove(pos).unwrap();
+                order.push_back(key);
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                return Ok(dm);
+            }
+        }
+        // compute without holding the lock, so other patterns are not blocked by the inversion
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let dm = Arc::new(compute()?);
+        let mut guard = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        let DecodingCacheInner { map, order } = &mut *guard;
+        if map.insert(erased.to_vec(), Arc::clone(&dm)).is_none() {
+            order.push_back(erased.to_vec());
+            if order.len() > self.capacity.get() {
+                let evicted = order.pop_front().unwrap();
+                map.remove(&evicted);
+            }
+        }
+        Ok(dm)
+    }
+
+    fn stats(&self) -> DecodingCacheStats {
+        let guard = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        DecodingCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            len: guard.map.len(),
+            capacity: self.capacity.get(),
+        }
+    }
 }
 
 impl ErasureCode {
@@ -496,44 +1376,92 @@ impl ErasureCode {
         }
     }
 
-    fn _encode_parity<T: AsRef<[u8]>, U: AsMut<[u8]>>(
-        &self,
-        source: impl AsRef<[T]>,
-        mut parity: U,
-    ) -> Result<(), Error> {
-        if source.as_ref().len() != self.k as usize {
-            return Err(Error::invalid_arguments(
-                "source must be the same length as k",
-            ));
+    /// Return the packet size, or `None` if the technique does not use packets.
+    pub fn packet_size(&self) -> Option<i32> {
+        match &self.tech {
+            TechInner::Matrix(_) => None,
+            TechInner::BitMatrix(_, packet_size) => Some(*packet_size),
+            TechInner::Schedule(schedule) => Some(schedule.packet_size),
+            TechInner::ScheduleCache(schedule) => Some(schedule.packet_size),
         }
-        let parity = parity.as_mut();
-        let src = source
-            .as_ref()
-            .iter()
-            .map(|s| s.as_ref())
-            .map(|s| {
-                if s.len() % crate::MACHINE_LONG_SIZE != 0 {
-                    return Err(Error::NotAligned(s.len()));
-                }
-                if s.len() != parity.len() {
-                    Err(Error::invalid_arguments(
-                        "source and parity must be the same length",
-                    ))
-                } else {
-                    Ok(s)
-                }
-            })
-            .map_ok(|s| s.as_ptr() as *mut ::std::ffi::c_char)
-            .try_collect::<_, Vec<_>, Error>()?;
-        unsafe {
-            jerasure_sys::jerasure::jerasure_do_parity(
-                self.k,
-                src.as_ptr() as *mut *mut ::std::ffi::c_char,
-                parity.as_mut_ptr() as *mut ::std::ffi::c_char,
-                parity.len().try_into().unwrap(),
-            );
+    }
+
+    /// Return the Galois field set by [`ErasureCodeBuilder::galois_field`],
+    /// or `None` if the code computes in the default field of Jerasure.
+    pub fn galois_field(&self) -> Option<&GaloisField> {
+        self.gf.as_ref()
+    }
+
+    /// Return the statistics of the decoding cache,
+    /// or `None` if it is not enabled by [`ErasureCodeBuilder::decoding_cache`].
+    pub fn decoding_cache_stats(&self) -> Option<DecodingCacheStats> {
+        self.cache.as_ref().map(DecodingCache::stats)
+    }
+
+    /// Return a copy of the coding matrix, with `m` rows and `k` columns in row-major order.
+    ///
+    /// The element $(i, j)$ is the coefficient of the data device $j$ in the coding device $i$,
+    /// and the identity part of the generator matrix is omitted.
+    /// The returned matrix can be passed to [`ErasureCodeBuilder::coding_matrix`] to rebuild the code.
+    ///
+    /// Return `None` if the coding method only defines a bit-matrix, e.g. `Liberation`.
+    pub fn coding_matrix(&self) -> Option<Vec<i32>> {
+        match &self.tech {
+            TechInner::Matrix(mat) => Some(mat),
+            _ => self.mat.as_ref(),
+        }
+        .map(|mat| mat.as_slice().to_vec())
+    }
+
+    /// Return a copy of the coding bit-matrix, with $m \times w$ rows and $k \times w$ columns
+    /// in row-major order.
+    ///
+    /// The returned bit-matrix can be passed to [`ErasureCodeBuilder::coding_bitmatrix`]
+    /// to rebuild the code.
+    ///
+    /// Return `None` if the technique is `Matrix`, which does not use a bit-matrix.
+    pub fn coding_bitmatrix(&self) -> Option<Vec<i32>> {
+        match &self.tech {
+            TechInner::Matrix(_) => None,
+            TechInner::BitMatrix(bmat, _) => Some(bmat),
+            TechInner::Schedule(schedule) => Some(&schedule.bmat),
+            TechInner::ScheduleCache(schedule) => Some(&schedule.bmat),
+        }
+        .map(|bmat| bmat.as_slice().to_vec())
+    }
+
+    /// Return the number of ones in the bit-matrix of the coding matrix.
+    ///
+    /// The number of ones determines the number of XOR operations
+    /// performed by the bit-matrix techniques, so fewer ones means faster coding.
+    /// For the `Matrix` technique, the number of ones of the bit-matrix that the
+    /// coding matrix would be converted to is returned.
+    pub fn n_ones(&self) -> usize {
+        let count = |bmat: &Matrix| bmat.as_slice().iter().filter(|&&e| e == 1).count();
+        match &self.tech {
+            TechInner::Matrix(mat) if self.gf.is_some() => {
+                let gf = self.gf.clone().unwrap();
+                let mat = crate::matrix::Matrix::new(
+                    gf,
+                    self.m as usize,
+                    self.k as usize,
+                    mat.as_slice(),
+                )
+                .unwrap();
+                crate::matrix::BitMatrix::from(&mat).n_ones()
+            }
+            TechInner::Matrix(mat) => mat
+                .as_slice()
+                .iter()
+                .map(
+                    |&e| unsafe { jerasure_sys::jerasure::cauchy_n_ones(e, self.w.as_cint()) }
+                        as usize,
+                )
+                .sum(),
+            TechInner::BitMatrix(bmat, _) => count(bmat),
+            TechInner::Schedule(schedule) => count(&schedule.bmat),
+            TechInner::ScheduleCache(schedule) => count(&schedule.bmat),
         }
-        Ok(())
     }
 
     /// Encode the data and generate coding parity.
@@ -544,6 +1472,14 @@ impl ErasureCode {
     ///
     /// # Requires
     /// * All the buffers must be aligned to the machine long size, and be the same length.
+    /// * For the techniques other than `Matrix`, the length must be a multiple of
+    ///   `packet_size * w`, since Jerasure encodes whole packets of w rows.
+    ///
+    /// # Errors
+    /// - [`Error::InvalidArguments`] if the number of data or coding buffers is wrong,
+    ///   the buffers are not the same length, or the length is not a multiple of
+    ///   `packet_size * w` for the techniques other than `Matrix`.
+    /// - [`Error::NotAligned`] if a buffer is not aligned.
     pub fn encode<T: AsRef<[u8]>, U: AsMut<[u8]>>(
         &self,
         data: impl AsRef<[T]>,
@@ -566,6 +1502,25 @@ impl ErasureCode {
         let data_ptrs = src.as_ptr() as *mut *mut ::std::ffi::c_char;
         let coding_ptrs = parity.as_ptr() as *mut *mut ::std::ffi::c_char;
         match &self.tech {
+            TechInner::Matrix(mat) if self.gf.is_some() => {
+                for (row, &dest) in mat.as_slice().chunks(self.k as usize).zip(&parity) {
+                    unsafe { self.matrix_dotprod(row, &src, dest, len)? };
+                }
+            }
+            TechInner::Matrix(_) if matches!(self.method, CodingMethod::ReedSolR6) => {
+                let ret = unsafe {
+                    jerasure_sys::jerasure::reed_sol_r6_encode(
+                        self.k,
+                        self.w.as_cint(),
+                        data_ptrs,
+                        coding_ptrs,
+                        len.try_into().unwrap(),
+                    )
+                };
+                if ret == 0 {
+                    return Err(Error::other("Failed to encode"));
+                }
+            }
             TechInner::Matrix(mat) => unsafe {
                 jerasure_sys::jerasure::jerasure_matrix_encode(
                     self.k,
@@ -617,6 +1572,220 @@ impl ErasureCode {
         Ok(())
     }
 
+    /// Encode the data and generate a single coding device.
+    ///
+    /// Only the row of the coding device `index` is computed, with the dot-product of
+    /// the coding matrix, or of the bit-matrix for the techniques other than `Matrix`,
+    /// so a replaced coding device is regenerated without writing the other ones.
+    ///
+    /// # Arguments
+    /// * `index` - The index of the coding device, in the range k..k+m.
+    /// * `data` - The data to encode, which must be a slice of `k` buffers of the same length.
+    /// * `out` - The buffer to store the coding device.
+    ///
+    /// # Requires
+    /// * All the buffers must be aligned to the machine long size, and be the same length.
+    ///
+    /// # Errors
+    /// - [`Error::InvalidArguments`] if `index` is out of the range k..k+m, the number of data
+    ///   devices is not `k`, the buffers are not the same length, or the length is not
+    ///   a multiple of `packet_size * w` for the techniques other than `Matrix`.
+    /// - [`Error::NotAligned`] if a buffer is not aligned.
+    pub fn encode_parity<T: AsRef<[u8]>>(
+        &self,
+        index: i32,
+        data: impl AsRef<[T]>,
+        mut out: impl AsMut<[u8]>,
+    ) -> Result<(), Error> {
+        if !(self.k..self.k + self.m).contains(&index) {
+            return Err(Error::invalid_arguments(format!(
+                "coding index({index}) out of bounds({}..{})",
+                self.k,
+                self.k + self.m
+            )));
+        }
+        let data = data.as_ref();
+        let out = out.as_mut();
+        if data.len() != self.k as usize {
+            return Err(Error::invalid_arguments(format!(
+                "source must have k({}) elements",
+                self.k
+            )));
+        }
+        let len = out.len();
+        for l in data.iter().map(|s| s.as_ref().len()).chain([len]) {
+            if !l.is_multiple_of(crate::MACHINE_LONG_SIZE) {
+                return Err(Error::NotAligned(l));
+            }
+            if l != len {
+                return Err(Error::invalid_arguments(
+                    "source and parity must be the same length",
+                ));
+            }
+        }
+        self.check_packet_alignment(len)?;
+
+        let src = data
+            .iter()
+            .map(|s| s.as_ref().as_ptr() as *mut ::std::ffi::c_char)
+            .collect::<Vec<_>>();
+        let dest = [out.as_mut_ptr() as *mut ::std::ffi::c_char];
+        let data_ptrs = src.as_ptr() as *mut *mut ::std::ffi::c_char;
+        let coding_ptrs = dest.as_ptr() as *mut *mut ::std::ffi::c_char;
+        // the coding device is the only one of `coding_ptrs`, so its id is `k`
+        let row = self.generator_rows(&[index]);
+        match self.packet_size() {
+            None => unsafe { self.matrix_dotprod(&row, &src, dest[0], len)? },
+            Some(packet_size) => unsafe {
+                jerasure_sys::jerasure::jerasure_bitmatrix_dotprod(
+                    self.k,
+                    self.w.as_cint(),
+                    row.as_ptr() as *mut c_int,
+                    std::ptr::null_mut(),
+                    self.k,
+                    data_ptrs,
+                    coding_ptrs,
+                    len.try_into().unwrap(),
+                    packet_size,
+                )
+            },
+        }
+        Ok(())
+    }
+
+    /// Update the coding devices in place after the data device `index` is rewritten.
+    ///
+    /// Only the old and new contents of the data device are read, so a small write
+    /// touches `1 + m` devices instead of re-encoding the whole stripe.
+    ///
+    /// # Arguments
+    /// * `index` - The index of the rewritten data device, in the range 0..k.
+    /// * `old` - The old content of the data device.
+    /// * `new` - The new content of the data device.
+    /// * `code` - The coding devices, which must be a slice of `m` buffers of the same length.
+    ///
+    /// # Requires
+    /// * All the buffers must be aligned to the machine long size, and be the same length.
+    ///
+    /// # Errors
+    /// - [`Error::InvalidArguments`] if `old` and `new` are not the same length, or the length
+    ///   is not a multiple of `packet_size * w` for the techniques other than `Matrix`.
+    /// - Any other error of [`update_parity_with_delta`](Self::update_parity_with_delta).
+    pub fn update_parity<U: AsMut<[u8]>>(
+        &self,
+        index: i32,
+        old: impl AsRef<[u8]>,
+        new: impl AsRef<[u8]>,
+        code: impl AsMut<[U]>,
+    ) -> Result<(), Error> {
+        let (old, new) = (old.as_ref(), new.as_ref());
+        if old.len() != new.len() {
+            return Err(Error::invalid_arguments(format!(
+                "old and new content must be the same length: old.len({}) != new.len({})",
+                old.len(),
+                new.len()
+            )));
+        }
+        let delta = old.iter().zip(new).map(|(o, n)| o ^ n).collect_vec();
+        self.update_parity_with_delta(index, delta, code)
+    }
+
+    /// Update the coding devices in place with the `delta` of the data device `index`,
+    /// which is the XOR of its old and new contents.
+    ///
+    /// Each coding device is added the delta multiplied by its coefficient of the
+    /// data device, with [`GaloisField::region_multiply`](crate::galois::GaloisField::region_multiply)
+    /// for the `Matrix` technique, and with the bit-matrix for the other techniques.
+    ///
+    /// # Arguments
+    /// * `index` - The index of the rewritten data device, in the range 0..k.
+    /// * `delta` - The XOR of the old and new contents of the data device.
+    /// * `code` - The coding devices, which must be a slice of `m` buffers of the same length.
+    ///
+    /// # Requires
+    /// * All the buffers must be aligned to the machine long size, and be the same length.
+    ///
+    /// # Errors
+    /// - [`Error::InvalidArguments`] if `index` is out of the range 0..k, the number of coding
+    ///   devices is not `m`, the buffers are not the same length, or the length is not
+    ///   a multiple of `packet_size * w` for the techniques other than `Matrix`.
+    /// - [`Error::NotAligned`] if a buffer is not aligned.
+    pub fn update_parity_with_delta<U: AsMut<[u8]>>(
+        &self,
+        index: i32,
+        delta: impl AsRef<[u8]>,
+        mut code: impl AsMut<[U]>,
+    ) -> Result<(), Error> {
+        if !(0..self.k).contains(&index) {
+            return Err(Error::invalid_arguments(format!(
+                "data index({index}) out of bounds(0..{})",
+                self.k
+            )));
+        }
+        let delta = delta.as_ref();
+        let code = code.as_mut();
+        if code.len() != self.m as usize {
+            return Err(Error::invalid_arguments(format!(
+                "parity must have m({}) elements",
+                self.m,
+            )));
+        }
+        let len = delta.len();
+        for l in std::iter::once(len).chain(code.iter_mut().map(|p| p.as_mut().len())) {
+            if !l.is_multiple_of(crate::MACHINE_LONG_SIZE) {
+                return Err(Error::NotAligned(l));
+            }
+            if l != len {
+                return Err(Error::invalid_arguments(
+                    "delta and parity must be the same length",
+                ));
+            }
+        }
+        self.check_packet_alignment(len)?;
+
+        let k = self.k as usize;
+        let idx = index as usize;
+        match &self.tech {
+            TechInner::Matrix(mat) => {
+                let gf = self.galois_field_or_default()?;
+                for (row, parity) in mat.as_slice().chunks(k).zip(code) {
+                    if row[idx] != 0 {
+                        gf.region_multiply(delta, row[idx], 1, parity.as_mut())?;
+                    }
+                }
+            }
+            _ => {
+                let w = usize::from(self.w.to_u8());
+                let packet_size = self.packet_size().unwrap() as usize;
+                let bmat = match &self.tech {
+                    TechInner::BitMatrix(bmat, _) => bmat,
+                    TechInner::Schedule(schedule) => &schedule.bmat,
+                    TechInner::ScheduleCache(schedule) => &schedule.bmat,
+                    TechInner::Matrix(_) => unreachable!(),
+                }
+                .as_slice();
+                // the `w x w` block of the bit-matrix of coding device `i` and data device `idx`
+                let bit = |i: usize, r: usize, c: usize| bmat[(i * w + r) * k * w + idx * w + c];
+                for (i, parity) in code.iter_mut().enumerate() {
+                    let parity = parity.as_mut();
+                    for (p, d) in parity
+                        .chunks_exact_mut(w * packet_size)
+                        .zip(delta.chunks_exact(w * packet_size))
+                    {
+                        for r in 0..w {
+                            let dest = &mut p[r * packet_size..(r + 1) * packet_size];
+                            for c in (0..w).filter(|&c| bit(i, r, c) == 1) {
+                                let src = &d[c * packet_size..(c + 1) * packet_size];
+                                dest.iter_mut().zip(src).for_each(|(p, d)| *p ^= d);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Decode the data and recover the erased data.
     ///
     /// # Arguments
@@ -627,38 +1796,44 @@ impl ErasureCode {
     ///
     /// # Requires
     /// * All the buffers must be aligned to the machine long size, and be the same length.
+    /// * For the techniques other than `Matrix`, the length must be a multiple of
+    ///   `packet_size * w`, as for [`encode`](Self::encode).
     /// * The erased indices must be in the range of 0..k+m.
     /// * The number of erased indices must be less than or equal to `m`.
     /// * The erased indices must be unique.
     ///
+    /// # Errors
+    /// - [`Error::InvalidArguments`] if the erased indices are invalid, the number of data or
+    ///   coding buffers is wrong, the buffers are not the same length, or the length is not
+    ///   a multiple of `packet_size * w` for the techniques other than `Matrix`.
+    /// - [`Error::TooManyErasure`] if more than `m` devices are erased.
+    /// - [`Error::NotAligned`] if a buffer is not aligned.
+    ///
     /// # Note
     /// The erased devices may not be recovered even if the number of erased devices is less than or equal to `m`.
     /// This is because the coding matrix may not be full rank with large `k` and `m`.
     /// In this case, the function will return an error.
+    ///
+    /// If the decoding cache is enabled by [`ErasureCodeBuilder::decoding_cache`], the decoding
+    /// matrix of the erasure pattern is cached and the erased devices are recovered as
+    /// [`decode_with_matrix`](Self::decode_with_matrix) does.
     pub fn decode<T: AsMut<[u8]>>(
         &self,
         mut data: impl AsMut<[T]>,
         mut code: impl AsMut<[T]>,
         erased: &[i32],
     ) -> Result<(), Error> {
-        use iter_tools::prelude::*;
-        let erased: Result<Vec<_>, Error> = erased
-            .iter()
-            .sorted()
-            .dedup()
-            .map(|&i| {
-                if 0 <= i && i < self.k + self.m {
-                    Ok(i)
-                } else {
-                    Err(Error::invalid_arguments("erased index out of bounds"))
-                }
-            })
-            .chain(std::iter::once(Ok(-1)))
-            .try_collect();
-        let erased = erased?;
-        if erased.len() - 1 > self.m as usize {
-            return Err(Error::too_many_erasure(erased.len() as i32 - 1, self.m));
+        let mut erased = self.check_erased(erased)?;
+        if let Some(cache) = &self.cache {
+            let dm = cache.get_or_try_insert(&erased, || self.decoding_matrix(&erased))?;
+            return self.decode_with_matrix(&dm, data, code);
         }
+        // Jerasure decodes with the matrices in its default field only
+        if let (TechInner::Matrix(_), Some(_)) = (&self.tech, &self.gf) {
+            let dm = self.decoding_matrix(&erased)?;
+            return self.decode_with_matrix(&dm, data, code);
+        }
+        erased.push(-1);
         self.check_decode_buffer(data.as_mut(), code.as_mut())?;
 
         let len = data.as_mut().first_mut().unwrap().as_mut().len();
@@ -675,9 +1850,16 @@ impl ErasureCode {
             .map(|s| s.as_mut_ptr() as *mut ::std::ffi::c_char)
             .collect::<Vec<_>>();
 
-        let row_k_ones = matches!(self.method, CodingMethod::ReedSolVand)
-            .then_some(1)
-            .unwrap_or(0);
+        let row_k_ones = matches!(
+            self.method,
+            CodingMethod::ReedSolVand
+                | CodingMethod::ReedSolR6
+                | CodingMethod::Liberation
+                | CodingMethod::Liber8tion
+                | CodingMethod::BlaumRoth
+        )
+        .then_some(1)
+        .unwrap_or(0);
         let erasures_ptr = erased.as_ptr() as *mut i32;
         let data_ptrs = src.as_ptr() as *mut *mut ::std::ffi::c_char;
         let coding_ptrs = parity.as_ptr() as *mut *mut ::std::ffi::c_char;
@@ -738,6 +1920,31 @@ impl ErasureCode {
                     return Err(Error::other("Failed to decode"));
                 }
             }
+            TechInner::ScheduleCache(schedule) if schedule.cache.is_null() => {
+                erased.pop();
+                if erased.is_empty() {
+                    return Ok(());
+                }
+                let decoding = self.decoding_schedule(schedule, &erased)?;
+                let ptr = |id: i32| match id < self.k {
+                    true => src[id as usize],
+                    false => parity[(id - self.k) as usize],
+                };
+                let survivors = decoding.survivors.iter().map(|&id| ptr(id)).collect_vec();
+                let targets = decoding.erased.iter().map(|&id| ptr(id)).collect_vec();
+                unsafe {
+                    jerasure_sys::jerasure::jerasure_schedule_encode(
+                        self.k,
+                        targets.len() as c_int,
+                        self.w.as_cint(),
+                        decoding.schedule.inner,
+                        survivors.as_ptr() as *mut *mut ::std::ffi::c_char,
+                        targets.as_ptr() as *mut *mut ::std::ffi::c_char,
+                        len.try_into().unwrap(),
+                        schedule.packet_size,
+                    )
+                };
+            }
             TechInner::ScheduleCache(schedule) => {
                 let ret = unsafe {
                     jerasure_sys::jerasure::jerasure_schedule_decode_cache(
@@ -760,38 +1967,942 @@ impl ErasureCode {
 
         Ok(())
     }
-}
 
-impl ErasureCode {
-    fn check_encode_buffer<T: AsRef<[u8]>, U: AsMut<[u8]>>(
-        &self,
-        source: impl AsRef<[T]>,
-        mut parity: impl AsMut<[U]>,
-    ) -> Result<(), Error> {
-        let source = source.as_ref();
-        let parity = parity.as_mut();
-        if source.len() != self.k as usize {
+    /// Decode the shards of a stripe, where the missing shards are erased.
+    ///
+    /// Unlike [`decode`](Self::decode), the erased devices are not passed separately:
+    /// a shard set to `None` is erased, and it is allocated and recovered in place,
+    /// so the shards and the erasure pattern cannot disagree.
+    ///
+    /// # Arguments
+    /// * `shards` - The `k + m` shards, the `k` data devices followed by the `m` coding devices.
+    ///
+    /// # Requires
+    /// * All the present shards must be aligned to the machine long size, and be the same length.
+    ///
+    /// # Errors
+    /// - [`Error::InvalidArguments`] if the number of shards is not `k + m`,
+    ///   or the present shards are not the same length.
+    /// - [`Error::TooManyErasure`] if more than `m` shards are missing.
+    /// - Any error of [`decode`](Self::decode).
+    pub fn decode_shards(&self, shards: &mut [Option<Vec<u8>>]) -> Result<(), Error> {
+        let n = (self.k + self.m) as usize;
+        if shards.len() != n {
             return Err(Error::invalid_arguments(format!(
-                "source must have k({}) elements",
-                self.k
+                "expect {n} shards, got {}",
+                shards.len()
             )));
         }
-        if parity.len() != self.m as usize {
-            return Err(Error::invalid_arguments(format!(
-                "parity must have m({}) elements",
-                self.m,
-            )));
+        let erased = (0..n as i32)
+            .filter(|&i| shards[i as usize].is_none())
+            .collect_vec();
+        if erased.is_empty() {
+            return Ok(());
         }
-        let len = source.first().unwrap().as_ref().len();
-        for s in source.as_ref().iter() {
-            let s = s.as_ref();
-            if s.len() % crate::MACHINE_LONG_SIZE != 0 {
-                return Err(Error::NotAligned(s.as_ref().len()));
-            }
-            if s.len() != len {
-                return Err(Error::invalid_arguments(
-                    "source and parity must be the same length",
-                ));
+        if erased.len() > self.m as usize {
+            return Err(Error::too_many_erasure(erased.len() as i32, self.m));
+        }
+        let len = shards.iter().flatten().map(Vec::len).next().unwrap();
+        if shards.iter().flatten().any(|s| s.len() != len) {
+            return Err(Error::invalid_arguments("shards must be the same length"));
+        }
+
+        let mut bufs = shards
+            .iter_mut()
+            .map(|s| s.take().unwrap_or_else(|| vec![0; len]))
+            .collect_vec();
+        let mut code = bufs.split_off(self.k as usize);
+        let mut data = bufs;
+        let ret = self.decode(&mut data, &mut code, &erased);
+        // put the buffers back, leaving the erased shards missing on failure
+        for (i, (shard, buf)) in shards
+            .iter_mut()
+            .zip(data.into_iter().chain(code))
+            .enumerate()
+        {
+            if ret.is_ok() || !erased.contains(&(i as i32)) {
+                *shard = Some(buf);
+            }
+        }
+        ret
+    }
+
+    /// Compute the decoding matrix of the erasure pattern `erased`.
+    ///
+    /// The decoding matrix expresses each erased device in terms of `k` surviving devices,
+    /// so it can be computed once and reused by [`decode_with_matrix`](Self::decode_with_matrix)
+    /// for all the stripes with the same erasure pattern, instead of inverting a matrix
+    /// on every call to [`decode`](Self::decode).
+    ///
+    /// # Arguments
+    /// * `erased` - The indices of the erased devices, with the same convention as [`decode`](Self::decode).
+    ///
+    /// # Errors
+    /// - [`Error::InvalidArguments`] if an erased index is out of the range 0..k+m.
+    /// - [`Error::TooManyErasure`] if more than `m` devices are erased.
+    /// - [`Error::Other`] if the erased devices cannot be recovered from the survivors.
+    pub fn decoding_matrix(&self, erased: &[i32]) -> Result<DecodingMatrix, Error> {
+        let erased = self.check_erased(erased)?;
+        let survivors = (0..self.k + self.m)
+            .filter(|i| !erased.contains(i))
+            .take(self.k as usize)
+            .collect::<Vec<_>>();
+        let rows = self.recovery_rows(&survivors, &erased)?;
+        Ok(DecodingMatrix {
+            k: self.k,
+            m: self.m,
+            w: self.w.to_u8(),
+            block_size: self.block_size(),
+            fingerprint: self.fingerprint,
+            erased,
+            survivors,
+            rows,
+        })
+    }
+
+    /// Recover the erased devices with a decoding matrix computed by
+    /// [`decoding_matrix`](Self::decoding_matrix).
+    ///
+    /// The erased devices are recovered from the survivors of the decoding matrix only,
+    /// so the content of the other devices is ignored.
+    ///
+    /// # Arguments
+    /// * `decoding_matrix` - The decoding matrix of the erasure pattern.
+    /// * `data` - The data devices, which must be a slice of `k` buffers of the same length.
+    /// * `code` - The coding devices, which must be a slice of `m` buffers of the same length.
+    ///
+    /// # Errors
+    /// - [`Error::InvalidArguments`] if the decoding matrix is not computed by a code with the
+    ///   same `k`, `m`, `w`, technique, coding matrix or bit-matrix and Galois field,
+    ///   or the buffers are invalid as in [`decode`](Self::decode).
+    pub fn decode_with_matrix<T: AsMut<[u8]>>(
+        &self,
+        decoding_matrix: &DecodingMatrix,
+        data: impl AsMut<[T]>,
+        code: impl AsMut<[T]>,
+    ) -> Result<(), Error> {
+        self.decode_targets_with_matrix(decoding_matrix, &decoding_matrix.erased, data, code)
+    }
+
+    /// Recover only the `targets` among the erased devices.
+    ///
+    /// The decoding matrix of the erasure pattern is computed, or taken from the decoding cache
+    /// if it is enabled by [`ErasureCodeBuilder::decoding_cache`], and only the rows of the
+    /// targets are applied. A survivor is only read if its coefficient in the row of a target
+    /// is not zero, so the other devices, including the erased devices not in `targets`,
+    /// are neither read nor written.
+    ///
+    /// # Arguments
+    /// * `data` - The data devices, which must be a slice of `k` buffers of the same length.
+    /// * `code` - The coding devices, which must be a slice of `m` buffers of the same length.
+    /// * `erased` - The indices of the erased devices, with the same convention as [`decode`](Self::decode).
+    /// * `targets` - The indices of the erased devices to recover.
+    ///
+    /// # Errors
+    /// - [`Error::InvalidArguments`] if a target is not erased, or the arguments are invalid
+    ///   as in [`decode`](Self::decode).
+    /// - [`Error::TooManyErasure`] if more than `m` devices are erased.
+    /// - [`Error::Other`] if the erased devices cannot be recovered from the survivors.
+    pub fn decode_partial<T: AsMut<[u8]>>(
+        &self,
+        data: impl AsMut<[T]>,
+        code: impl AsMut<[T]>,
+        erased: &[i32],
+        targets: &[i32],
+    ) -> Result<(), Error> {
+        let erased = self.check_erased(erased)?;
+        if let Some(t) = targets.iter().find(|t| !erased.contains(t)) {
+            return Err(Error::invalid_arguments(format!(
+                "target({t}) is not in the erased devices {erased:?}"
+            )));
+        }
+        match &self.cache {
+            Some(cache) => {
+                let dm = cache.get_or_try_insert(&erased, || self.decoding_matrix(&erased))?;
+                self.decode_targets_with_matrix(&dm, targets, data, code)
+            }
+            None => {
+                let dm = self.decoding_matrix(&erased)?;
+                self.decode_targets_with_matrix(&dm, targets, data, code)
+            }
+        }
+    }
+
+    /// Plan the repair of the devices `targets` from the devices `available`.
+    ///
+    /// The plan reads at most `k` of the available devices, preferring the ones with
+    /// the lowest indices, and drops the ones whose coefficients are all zero,
+    /// so [`RepairPlan::sources`] tells which devices to fetch before calling
+    /// [`repair`](Self::repair).
+    ///
+    /// # Arguments
+    /// * `targets` - The indices of the devices to repair, in the range 0..k+m.
+    /// * `available` - The indices of the devices that can be read, in the range 0..k+m.
+    ///
+    /// # Errors
+    /// - [`Error::InvalidArguments`] if an index is out of range, or a target is available.
+    /// - [`Error::TooManyErasure`] if fewer than `k` devices are available.
+    /// - [`Error::Other`] if the targets cannot be recovered from the available devices.
+    pub fn repair_plan(&self, targets: &[i32], available: &[i32]) -> Result<RepairPlan, Error> {
+        let n = self.k + self.m;
+        if let Some(i) = targets.iter().chain(available).find(|&&i| i < 0 || i >= n) {
+            return Err(Error::invalid_arguments(format!(
+                "device index({i}) out of bounds(0..{n})"
+            )));
+        }
+        let targets = targets.iter().copied().sorted().dedup().collect_vec();
+        let available = available.iter().copied().sorted().dedup().collect_vec();
+        if let Some(t) = targets.iter().find(|t| available.contains(t)) {
+            return Err(Error::invalid_arguments(format!(
+                "target({t}) is available"
+            )));
+        }
+        if available.len() < self.k as usize {
+            return Err(Error::too_many_erasure(n - available.len() as i32, self.m));
+        }
+        let survivors = &available[..self.k as usize];
+        let rows = self.recovery_rows(survivors, &targets)?;
+
+        // drop the survivors whose coefficients are all zero
+        let b = self.block_size();
+        let cols = self.k as usize * b;
+        let used = (0..self.k as usize)
+            .filter(|&j| {
+                rows.chunks(cols.max(1))
+                    .any(|row| row[j * b..(j + 1) * b].iter().any(|&e| e != 0))
+            })
+            .collect_vec();
+        let coefficients = rows
+            .chunks(cols.max(1))
+            .flat_map(|row| used.iter().flat_map(move |&j| &row[j * b..(j + 1) * b]))
+            .copied()
+            .collect();
+        Ok(RepairPlan {
+            k: self.k,
+            m: self.m,
+            w: self.w.to_u8(),
+            block_size: b,
+            fingerprint: self.fingerprint,
+            sources: used.iter().map(|&j| survivors[j]).collect(),
+            targets,
+            coefficients,
+        })
+    }
+
+    /// Execute a repair plan computed by [`repair_plan`](Self::repair_plan).
+    ///
+    /// # Arguments
+    /// * `plan` - The repair plan.
+    /// * `sources` - The buffers of [`RepairPlan::sources`], in the same order.
+    /// * `targets` - The buffers to store [`RepairPlan::targets`], in the same order.
+    ///
+    /// # Requires
+    /// * All the buffers must be aligned to the machine long size, and be the same length.
+    ///
+    /// # Errors
+    /// - [`Error::InvalidArguments`] if the plan is not computed by a code with the same `k`, `m`,
+    ///   `w`, technique, coding matrix or bit-matrix and Galois field, the numbers of buffers do not match the plan, or the buffers are
+    ///   not the same length.
+    /// - [`Error::NotAligned`] if a buffer is not aligned.
+    pub fn repair<S: AsRef<[u8]>, T: AsMut<[u8]>>(
+        &self,
+        plan: &RepairPlan,
+        sources: impl AsRef<[S]>,
+        mut targets: impl AsMut<[T]>,
+    ) -> Result<(), Error> {
+        if (plan.k, plan.m, plan.w, plan.block_size, plan.fingerprint)
+            != (
+                self.k,
+                self.m,
+                self.w.to_u8(),
+                self.block_size(),
+                self.fingerprint,
+            )
+        {
+            return Err(Error::invalid_arguments(
+                "repair plan does not match the erasure code",
+            ));
+        }
+        let sources = sources.as_ref();
+        let targets = targets.as_mut();
+        if sources.len() != plan.sources.len() || targets.len() != plan.targets.len() {
+            return Err(Error::invalid_arguments(format!(
+                "repair plan requires {} sources and {} targets, got {} and {}",
+                plan.sources.len(),
+                plan.targets.len(),
+                sources.len(),
+                targets.len()
+            )));
+        }
+        let Some(len) = targets.first_mut().map(|t| t.as_mut().len()) else {
+            return Ok(());
+        };
+        for l in sources
+            .iter()
+            .map(|s| s.as_ref().len())
+            .chain(targets.iter_mut().map(|t| t.as_mut().len()))
+        {
+            if !l.is_multiple_of(crate::MACHINE_LONG_SIZE) {
+                return Err(Error::NotAligned(l));
+            }
+            if l != len {
+                return Err(Error::invalid_arguments(
+                    "sources and targets must be the same length",
+                ));
+            }
+        }
+        self.check_packet_alignment(len)?;
+        if sources.is_empty() {
+            targets.iter_mut().for_each(|t| t.as_mut().fill(0));
+            return Ok(());
+        }
+
+        // the sources are the data devices and the targets are the coding devices
+        // of the dot products
+        let n = sources.len() as c_int;
+        let src = sources
+            .iter()
+            .map(|s| s.as_ref().as_ptr() as *mut ::std::ffi::c_char)
+            .collect::<Vec<_>>();
+        let dest = targets
+            .iter_mut()
+            .map(|t| t.as_mut().as_mut_ptr() as *mut ::std::ffi::c_char)
+            .collect::<Vec<_>>();
+        let src_ids = (0..n).collect_vec();
+        let row_len = plan.coefficients.len() / plan.targets.len();
+        for (t, row) in plan.coefficients.chunks(row_len).enumerate() {
+            match self.packet_size() {
+                None => unsafe { self.matrix_dotprod(row, &src, dest[t], len)? },
+                Some(packet_size) => unsafe {
+                    jerasure_sys::jerasure::jerasure_bitmatrix_dotprod(
+                        n,
+                        self.w.as_cint(),
+                        row.as_ptr() as *mut c_int,
+                        src_ids.as_ptr() as *mut c_int,
+                        n + t as c_int,
+                        src.as_ptr() as *mut *mut ::std::ffi::c_char,
+                        dest.as_ptr() as *mut *mut ::std::ffi::c_char,
+                        len.try_into().unwrap(),
+                        packet_size,
+                    )
+                },
+            }
+        }
+        Ok(())
+    }
+
+    /// Verify that the coding devices match the data devices.
+    ///
+    /// The coding devices are recomputed from the data devices and compared with `code`.
+    ///
+    /// # Arguments
+    /// * `data` - The data devices, which must be a slice of `k` buffers of the same length.
+    /// * `code` - The coding devices, which must be a slice of `m` buffers of the same length.
+    ///
+    /// # Returns
+    /// The indices, in the range k..k+m, of the coding devices that do not match the data devices.
+    /// An empty vector means the stripe is consistent.
+    ///
+    /// # Errors
+    /// - [`Error::InvalidArguments`] or [`Error::NotAligned`] if the buffers are invalid as in
+    ///   [`encode`](Self::encode).
+    pub fn verify<T: AsRef<[u8]>, U: AsRef<[u8]>>(
+        &self,
+        data: impl AsRef<[T]>,
+        code: impl AsRef<[U]>,
+    ) -> Result<Vec<i32>, Error> {
+        let syndromes = self.syndromes(data, code)?;
+        Ok(self.mismatched(&syndromes))
+    }
+
+    /// Locate a single corrupted device of a stripe.
+    ///
+    /// The syndromes, which are the differences between the recomputed and the stored
+    /// coding devices, are checked against the hypothesis that each device in turn is
+    /// the only corrupted one. With `m >= 2`, exactly one hypothesis holds if a single
+    /// device is corrupted.
+    ///
+    /// # Arguments
+    /// * `data` - The data devices, which must be a slice of `k` buffers of the same length.
+    /// * `code` - The coding devices, which must be a slice of `m` buffers of the same length.
+    ///
+    /// # Returns
+    /// `None` if the stripe is consistent, or the index of the corrupted device, in the range 0..k+m.
+    ///
+    /// # Errors
+    /// - [`Error::NotSupported`] if `m < 2`.
+    /// - [`Error::InvalidArguments`] or [`Error::NotAligned`] if the buffers are invalid as in
+    ///   [`encode`](Self::encode).
+    /// - [`Error::Other`] if the corruption is not limited to a single device.
+    pub fn locate_corruption<T: AsRef<[u8]>, U: AsRef<[u8]>>(
+        &self,
+        data: impl AsRef<[T]>,
+        code: impl AsRef<[U]>,
+    ) -> Result<Option<i32>, Error> {
+        if self.m < 2 {
+            return Err(Error::not_supported(
+                "locating a corrupted device requires m >= 2",
+            ));
+        }
+        let mut syndromes = self.syndromes(data, code)?;
+        let mismatched = self.mismatched(&syndromes);
+        if mismatched.is_empty() {
+            return Ok(None);
+        }
+        // a corrupted coding device only changes its own syndrome
+        if let [idx] = mismatched[..] {
+            return Ok(Some(idx));
+        }
+        // a corrupted data device `i` with error `e` gives the syndromes of encoding
+        // `e` at device `i` and zeros elsewhere, so recover `e` from the first syndrome
+        // and check that it yields all the syndromes
+        let len = syndromes[0].len();
+        let mut errors = vec![vec![0; len]; self.k as usize];
+        for i in 0..self.k {
+            self.decode_partial(&mut errors, &mut syndromes, &[i], &[i])?;
+            if self.verify(&errors, &syndromes)?.is_empty() {
+                return Ok(Some(i));
+            }
+            errors[i as usize].fill(0);
+        }
+        Err(Error::other(format!(
+            "coding devices {mismatched:?} mismatch, more than one device is corrupted"
+        )))
+    }
+
+    /// Correct up to `m / 2` corrupted devices of a stripe in place, without being told
+    /// which ones are corrupted.
+    ///
+    /// Each set of at most `m / 2` devices is in turn assumed to be erased and decoded
+    /// from the other devices, until the decoded stripe is consistent. Since any two
+    /// stripes of an MDS code differ in at least `m + 1` devices, the consistent stripe
+    /// is unique if at most `m / 2` devices are corrupted.
+    ///
+    /// # Arguments
+    /// * `data` - The data devices, which must be a slice of `k` buffers of the same length.
+    /// * `code` - The coding devices, which must be a slice of `m` buffers of the same length.
+    ///
+    /// # Returns
+    /// The indices, in the range 0..k+m, of the repaired devices in ascending order.
+    /// An empty vector means the stripe is consistent.
+    ///
+    /// # Note
+    /// The number of decoded hypotheses grows as $\binom{k+m}{m/2}$, so this is meant for
+    /// scrubbing stripes that [`verify`](Self::verify) has found inconsistent.
+    ///
+    /// # Errors
+    /// - [`Error::NotSupported`] if the coding method is not a Reed-Solomon or Cauchy method,
+    ///   or `m < 2`.
+    /// - [`Error::InvalidArguments`] or [`Error::NotAligned`] if the buffers are invalid as in
+    ///   [`decode`](Self::decode).
+    /// - [`Error::Other`] if more than `m / 2` devices are corrupted.
+    pub fn correct<T: AsMut<[u8]>>(
+        &self,
+        mut data: impl AsMut<[T]>,
+        mut code: impl AsMut<[T]>,
+    ) -> Result<Vec<i32>, Error> {
+        if !matches!(
+            self.method,
+            CodingMethod::ReedSolVand
+                | CodingMethod::ReedSolR6
+                | CodingMethod::Cauchy
+                | CodingMethod::CauchyOrig
+                | CodingMethod::CauchyXY
+        ) {
+            return Err(Error::not_supported(format!(
+                "error correction is not supported for {:?}",
+                self.method
+            )));
+        }
+        if self.m < 2 {
+            return Err(Error::not_supported(
+                "correcting a corrupted device requires m >= 2",
+            ));
+        }
+        self.check_decode_buffer(data.as_mut(), code.as_mut())?;
+        let mut data = data.as_mut().iter_mut().map(|s| s.as_mut()).collect_vec();
+        let mut code = code.as_mut().iter_mut().map(|s| s.as_mut()).collect_vec();
+        if self.verify(&data, &code)?.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut trial_data = data.iter().map(|s| s.to_vec()).collect_vec();
+        let mut trial_code = code.iter().map(|s| s.to_vec()).collect_vec();
+        for t in 1..=(self.m / 2) as usize {
+            for erased in (0..self.k + self.m).combinations(t) {
+                // bypass the decoding cache, which would be flooded by the hypotheses
+                let Ok(dm) = self.decoding_matrix(&erased) else {
+                    continue;
+                };
+                for (trial, s) in trial_data.iter_mut().zip(&data) {
+                    trial.copy_from_slice(s);
+                }
+                for (trial, c) in trial_code.iter_mut().zip(&code) {
+                    trial.copy_from_slice(c);
+                }
+                self.decode_with_matrix(&dm, &mut trial_data, &mut trial_code)?;
+                if !self.verify(&trial_data, &trial_code)?.is_empty() {
+                    continue;
+                }
+                let mut repaired = Vec::new();
+                for id in erased {
+                    let (dest, src) = match id < self.k {
+                        true => (&mut *data[id as usize], &trial_data[id as usize]),
+                        false => (
+                            &mut *code[(id - self.k) as usize],
+                            &trial_code[(id - self.k) as usize],
+                        ),
+                    };
+                    if dest != src.as_slice() {
+                        dest.copy_from_slice(src);
+                        repaired.push(id);
+                    }
+                }
+                return Ok(repaired);
+            }
+        }
+        Err(Error::other(format!(
+            "more than {} devices are corrupted",
+            self.m / 2
+        )))
+    }
+
+    /// Return the syndromes of a stripe, which are the XOR of the coding devices
+    /// recomputed from `data` and the ones in `code`.
+    fn syndromes<T: AsRef<[u8]>, U: AsRef<[u8]>>(
+        &self,
+        data: impl AsRef<[T]>,
+        code: impl AsRef<[U]>,
+    ) -> Result<Vec<Vec<u8>>, Error> {
+        let code = code.as_ref();
+        if code.len() != self.m as usize {
+            return Err(Error::invalid_arguments(format!(
+                "parity must have m({}) elements",
+                self.m,
+            )));
+        }
+        let len = code[0].as_ref().len();
+        let mut syndromes = vec![vec![0; len]; self.m as usize];
+        self.encode(data, &mut syndromes)?;
+        for (syndrome, c) in syndromes.iter_mut().zip(code) {
+            let c = c.as_ref();
+            if c.len() != len {
+                return Err(Error::invalid_arguments(
+                    "source and parity must be the same length",
+                ));
+            }
+            syndrome.iter_mut().zip(c).for_each(|(s, c)| *s ^= c);
+        }
+        Ok(syndromes)
+    }
+
+    /// Return the indices of the coding devices whose syndromes are not zero.
+    fn mismatched(&self, syndromes: &[Vec<u8>]) -> Vec<i32> {
+        syndromes
+            .iter()
+            .positions(|s| s.iter().any(|&b| b != 0))
+            .map(|j| self.k + j as i32)
+            .collect()
+    }
+}
+
+/// The `DecodingMatrix` expresses the erased devices of an erasure pattern
+/// in terms of `k` surviving devices.
+///
+/// It is computed by [`ErasureCode::decoding_matrix`], and used by
+/// [`ErasureCode::decode_with_matrix`] to recover the erased devices without
+/// inverting a matrix again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodingMatrix {
+    k: i32,
+    m: i32,
+    w: u8,
+    block_size: usize,
+    /// The fingerprint of the code that computed the decoding matrix.
+    fingerprint: u64,
+    erased: Vec<i32>,
+    survivors: Vec<i32>,
+    rows: Vec<i32>,
+}
+
+impl DecodingMatrix {
+    /// Return the indices of the erased devices, in ascending order.
+    pub fn erased(&self) -> &[i32] {
+        &self.erased
+    }
+
+    /// Return the indices of the `k` surviving devices that the erased devices are recovered from,
+    /// in ascending order.
+    pub fn survivors(&self) -> &[i32] {
+        &self.survivors
+    }
+
+    /// Return whether the decoding matrix is a bit-matrix, which is the case
+    /// for all the techniques but `Matrix`.
+    pub fn is_bitmatrix(&self) -> bool {
+        self.block_size != 1
+    }
+
+    /// Return the decoding matrix in row-major order.
+    ///
+    /// For the `Matrix` technique, it has a row of `k` elements for each erased device,
+    /// whose element $j$ is the coefficient of the $j$-th survivor.
+    /// For the other techniques, it has $w$ rows of $k \times w$ bits for each erased device.
+    pub fn rows(&self) -> &[i32] {
+        &self.rows
+    }
+}
+
+/// The `RepairPlan` tells which devices to read to repair a set of devices,
+/// and how to combine them.
+///
+/// It is computed by [`ErasureCode::repair_plan`], and executed by [`ErasureCode::repair`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepairPlan {
+    k: i32,
+    m: i32,
+    w: u8,
+    block_size: usize,
+    /// The fingerprint of the code that computed the plan.
+    fingerprint: u64,
+    sources: Vec<i32>,
+    targets: Vec<i32>,
+    coefficients: Vec<i32>,
+}
+
+impl RepairPlan {
+    /// Return the indices of the devices to read, in ascending order.
+    pub fn sources(&self) -> &[i32] {
+        &self.sources
+    }
+
+    /// Return the indices of the devices to repair, in ascending order.
+    pub fn targets(&self) -> &[i32] {
+        &self.targets
+    }
+
+    /// Return whether the coefficients are a bit-matrix, which is the case
+    /// for all the techniques but `Matrix`.
+    pub fn is_bitmatrix(&self) -> bool {
+        self.block_size != 1
+    }
+
+    /// Return the coefficients in row-major order.
+    ///
+    /// For the `Matrix` technique, it has a row for each target, whose element $j$ is the
+    /// coefficient of the $j$-th source. For the other techniques, it has $w$ rows of
+    /// $sources \times w$ bits for each target.
+    pub fn coefficients(&self) -> &[i32] {
+        &self.coefficients
+    }
+}
+
+impl ErasureCode {
+    /// Hash the coding matrix, the coding bit-matrix and the Galois field,
+    /// which determine the decoding matrices and repair plans together with `k`, `m` and `w`.
+    fn coding_fingerprint(&self) -> u64 {
+        let mut hasher = std::hash::DefaultHasher::new();
+        self.coding_matrix().hash(&mut hasher);
+        self.coding_bitmatrix().hash(&mut hasher);
+        self.gf.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Recover the `targets` among the erased devices of the decoding matrix.
+    fn decode_targets_with_matrix<T: AsMut<[u8]>>(
+        &self,
+        dm: &DecodingMatrix,
+        targets: &[i32],
+        mut data: impl AsMut<[T]>,
+        mut code: impl AsMut<[T]>,
+    ) -> Result<(), Error> {
+        if (dm.k, dm.m, dm.w, dm.block_size, dm.fingerprint)
+            != (
+                self.k,
+                self.m,
+                self.w.to_u8(),
+                self.block_size(),
+                self.fingerprint,
+            )
+        {
+            return Err(Error::invalid_arguments(
+                "decoding matrix does not match the erasure code",
+            ));
+        }
+        self.check_decode_buffer(data.as_mut(), code.as_mut())?;
+
+        let len = data.as_mut().first_mut().unwrap().as_mut().len();
+        let src = data
+            .as_mut()
+            .iter_mut()
+            .map(|s| s.as_mut())
+            .map(|s| s.as_mut_ptr() as *mut ::std::ffi::c_char)
+            .collect::<Vec<_>>();
+        let parity = code
+            .as_mut()
+            .iter_mut()
+            .map(|s| s.as_mut())
+            .map(|s| s.as_mut_ptr() as *mut ::std::ffi::c_char)
+            .collect::<Vec<_>>();
+        let data_ptrs = src.as_ptr() as *mut *mut ::std::ffi::c_char;
+        let coding_ptrs = parity.as_ptr() as *mut *mut ::std::ffi::c_char;
+        let src_ids = dm.survivors.as_ptr() as *mut c_int;
+        let ptr = |id: i32| match id < self.k {
+            true => src[id as usize],
+            false => parity[(id - self.k) as usize],
+        };
+        let survivors = dm.survivors.iter().map(|&id| ptr(id)).collect_vec();
+        let row_len = dm.rows.len() / dm.erased.len().max(1);
+        for (row, &dest_id) in dm
+            .rows
+            .chunks(row_len.max(1))
+            .zip(&dm.erased)
+            .filter(|(_, id)| targets.contains(id))
+        {
+            match self.packet_size() {
+                None => unsafe { self.matrix_dotprod(row, &survivors, ptr(dest_id), len)? },
+                Some(packet_size) => unsafe {
+                    jerasure_sys::jerasure::jerasure_bitmatrix_dotprod(
+                        self.k,
+                        self.w.as_cint(),
+                        row.as_ptr() as *mut c_int,
+                        src_ids,
+                        dest_id,
+                        data_ptrs,
+                        coding_ptrs,
+                        len.try_into().unwrap(),
+                        packet_size,
+                    )
+                },
+            }
+        }
+        Ok(())
+    }
+
+    /// Return the rows expressing the devices `targets` in terms of the `k` devices `survivors`,
+    /// which are the rows of `targets` in the generator matrix multiplied by the inverse of
+    /// the rows of `survivors`.
+    fn recovery_rows(&self, survivors: &[i32], targets: &[i32]) -> Result<Vec<i32>, Error> {
+        let n = self.k as usize * self.block_size();
+        let gen_survivors = self.generator_rows(survivors);
+        let gen_targets = self.generator_rows(targets);
+        let failed = || {
+            Error::other(format!(
+                "devices {targets:?} cannot be recovered from devices {survivors:?}"
+            ))
+        };
+        let rows = match &self.tech {
+            TechInner::Matrix(_) => {
+                let gf = self.galois_field_or_default()?;
+                let inv = crate::matrix::Matrix::new(gf.clone(), n, n, gen_survivors)?
+                    .invert()
+                    .map_err(|_| failed())?;
+                crate::matrix::Matrix::new(gf, targets.len(), n, gen_targets)?
+                    .multiply(&inv)?
+                    .into_vec()
+            }
+            _ => {
+                let w = self.w.to_u8();
+                let inv = crate::matrix::BitMatrix::new(w, n, n, gen_survivors)?
+                    .invert()
+                    .map_err(|_| failed())?;
+                crate::matrix::BitMatrix::new(w, targets.len() * usize::from(w), n, gen_targets)?
+                    .multiply(&inv)?
+                    .into_vec()
+            }
+        };
+        Ok(rows)
+    }
+
+    /// Return the Galois field of the code, which is the default field of Jerasure
+    /// unless it is set by [`ErasureCodeBuilder::galois_field`].
+    fn galois_field_or_default(&self) -> Result<GaloisField, Error> {
+        match &self.gf {
+            Some(gf) => Ok(gf.clone()),
+            None => GaloisField::try_from_code_word(self.w)
+                .ok_or_else(|| Error::other("Failed to init galois field")),
+        }
+    }
+
+    /// Compute the dot product of the coding matrix `row` and the regions `srcs` into
+    /// the region `dest`, with Jerasure in its default field, or region by region
+    /// in the field of the code.
+    ///
+    /// # Safety
+    /// The regions must be valid for `len` bytes, and `dest` must not overlap with `srcs`.
+    unsafe fn matrix_dotprod(
+        &self,
+        row: &[i32],
+        srcs: &[*mut ::std::ffi::c_char],
+        dest: *mut ::std::ffi::c_char,
+        len: usize,
+    ) -> Result<(), Error> {
+        let Some(gf) = &self.gf else {
+            // the regions are the data devices and `dest` is the only coding device,
+            // whose id is the number of the data devices
+            let dest = [dest];
+            unsafe {
+                jerasure_sys::jerasure::jerasure_matrix_dotprod(
+                    srcs.len() as c_int,
+                    self.w.as_cint(),
+                    row.as_ptr() as *mut c_int,
+                    std::ptr::null_mut(),
+                    srcs.len() as c_int,
+                    srcs.as_ptr() as *mut *mut ::std::ffi::c_char,
+                    dest.as_ptr() as *mut *mut ::std::ffi::c_char,
+                    len.try_into().unwrap(),
+                )
+            };
+            return Ok(());
+        };
+        let dest = unsafe { std::slice::from_raw_parts_mut(dest as *mut u8, len) };
+        let mut add = 0;
+        for (&e, &src) in row.iter().zip(srcs).filter(|(e, _)| **e != 0) {
+            let src = unsafe { std::slice::from_raw_parts(src as *const u8, len) };
+            gf.region_multiply(src, e, add, &mut *dest)?;
+            add = 1;
+        }
+        if add == 0 {
+            dest.fill(0);
+        }
+        Ok(())
+    }
+
+    /// Validate the erased indices, and return them sorted and deduplicated.
+    fn check_erased(&self, erased: &[i32]) -> Result<Vec<i32>, Error> {
+        use iter_tools::prelude::*;
+        let erased: Vec<_> = erased
+            .iter()
+            .sorted()
+            .dedup()
+            .map(|&i| {
+                if 0 <= i && i < self.k + self.m {
+                    Ok(i)
+                } else {
+                    Err(Error::invalid_arguments("erased index out of bounds"))
+                }
+            })
+            .try_collect()?;
+        if erased.len() > self.m as usize {
+            return Err(Error::too_many_erasure(erased.len() as i32, self.m));
+        }
+        Ok(erased)
+    }
+
+    /// Return the rows of the generator matrix of the devices `ids`, where the data devices
+    /// are the rows of the identity matrix, and the coding devices are the rows of
+    /// the coding matrix, or the bit-matrix for the techniques other than `Matrix`.
+    fn generator_rows(&self, ids: &[i32]) -> Vec<i32> {
+        let (k, b) = (self.k as usize, self.block_size());
+        let cols = k * b;
+        let coding = match &self.tech {
+            TechInner::Matrix(mat) => mat,
+            TechInner::BitMatrix(bmat, _) => bmat,
+            TechInner::Schedule(schedule) => &schedule.bmat,
+            TechInner::ScheduleCache(schedule) => &schedule.bmat,
+        }
+        .as_slice();
+        let mut rows = Vec::with_capacity(ids.len() * b * cols);
+        for &id in ids {
+            let id = id as usize;
+            if id < k {
+                for r in id * b..(id + 1) * b {
+                    rows.extend((0..cols).map(|c| i32::from(c == r)));
+                }
+            } else {
+                rows.extend_from_slice(&coding[(id - k) * b * cols..][..b * cols]);
+            }
+        }
+        rows
+    }
+
+    /// Return the cached decoding schedule of the sorted erasure pattern `erased`,
+    /// or compute it from the decoding bit-matrix and insert it.
+    fn decoding_schedule(
+        &self,
+        schedule_cache: &ScheduleCache,
+        erased: &[i32],
+    ) -> Result<Arc<DecodingSchedule>, Error> {
+        let cached = schedule_cache
+            .decoding
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(erased)
+            .cloned();
+        if let Some(decoding) = cached {
+            return Ok(decoding);
+        }
+        let dm = self.decoding_matrix(erased)?;
+        let mut bmat = Matrix::from_slice(dm.rows())
+            .ok_or_else(|| Error::other("Failed to allocate decoding bit-matrix"))?;
+        let inner = unsafe {
+            jerasure_sys::jerasure::jerasure_smart_bitmatrix_to_schedule(
+                self.k,
+                erased.len() as c_int,
+                self.w.as_cint(),
+                bmat.as_mut_ptr(),
+            )
+        };
+        if inner.is_null() {
+            return Err(Error::other("Failed to create decoding schedule"));
+        }
+        let decoding = Arc::new(DecodingSchedule {
+            survivors: dm.survivors,
+            erased: dm.erased,
+            schedule: Schedule {
+                bmat,
+                packet_size: schedule_cache.packet_size,
+                inner,
+            },
+        });
+        Ok(Arc::clone(
+            schedule_cache
+                .decoding
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .entry(erased.to_vec())
+                .or_insert(decoding),
+        ))
+    }
+
+    /// Return the size of the blocks of the coding matrix, that is,
+    /// 1 for the `Matrix` technique, and `w` for the techniques using a bit-matrix.
+    fn block_size(&self) -> usize {
+        match self.tech {
+            TechInner::Matrix(_) => 1,
+            _ => usize::from(self.w.to_u8()),
+        }
+    }
+
+    fn check_encode_buffer<T: AsRef<[u8]>, U: AsMut<[u8]>>(
+        &self,
+        source: impl AsRef<[T]>,
+        mut parity: impl AsMut<[U]>,
+    ) -> Result<(), Error> {
+        let source = source.as_ref();
+        let parity = parity.as_mut();
+        if source.len() != self.k as usize {
+            return Err(Error::invalid_arguments(format!(
+                "source must have k({}) elements",
+                self.k
+            )));
+        }
+        if parity.len() != self.m as usize {
+            return Err(Error::invalid_arguments(format!(
+                "parity must have m({}) elements",
+                self.m,
+            )));
+        }
+        let len = source.first().unwrap().as_ref().len();
+        for s in source.as_ref().iter() {
+            let s = s.as_ref();
+            if s.len() % crate::MACHINE_LONG_SIZE != 0 {
+                return Err(Error::NotAligned(s.as_ref().len()));
+            }
+            if s.len() != len {
+                return Err(Error::invalid_arguments(
+                    "source and parity must be the same length",
+                ));
             }
         }
         for p in parity {
@@ -805,7 +2916,7 @@ impl ErasureCode {
                 ));
             }
         }
-        Ok(())
+        self.check_packet_alignment(len)
     }
 
     fn check_decode_buffer<T: AsMut<[u8]>, U: AsMut<[u8]>>(
@@ -850,6 +2961,20 @@ impl ErasureCode {
                 ));
             }
         }
+        self.check_packet_alignment(len)
+    }
+
+    /// Checks that `len` is a multiple of `packet_size * w` for the bit-matrix techniques,
+    /// otherwise Jerasure reports the partial packet and exits the process.
+    fn check_packet_alignment(&self, len: usize) -> Result<(), Error> {
+        if let Some(packet_size) = self.packet_size() {
+            let stripe = packet_size as usize * self.w.to_u8() as usize;
+            if !len.is_multiple_of(stripe) {
+                return Err(Error::invalid_arguments(format!(
+                    "buffer length({len}) must be a multiple of packet_size * w({stripe})"
+                )));
+            }
+        }
         Ok(())
     }
 }
@@ -6,6 +6,13 @@
 //! This module is designed to interface with low-level Galois field operations, provided by
 //! library `gf-complete`.
 
+use std::ffi::c_void;
+use std::ops::BitOr;
+use std::os::raw::c_int;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+
+use jerasure_sys::gf_complete::gf_t;
+
 use crate::{CodeWord, Error, MACHINE_LONG_SIZE};
 
 /// The `GaloisField` struct represents a Galois field GF(2^w) with a specified word size `w`.
@@ -13,23 +20,235 @@ use crate::{CodeWord, Error, MACHINE_LONG_SIZE};
 /// It provides methods for performing various operations in the Galois field, such as
 /// addition, multiplication, division, and inversion.
 ///
+/// The elements are `i32` for the word sizes in 1..=32, `u64` for [`CodeWord::W64`]
+/// and `u128` for [`CodeWord::W128`], each with its own set of operations.
+///
 /// # Note
-/// - The word size `w` must be in the range 1..=32.
+/// - The word size `w` must be in the range 1..=32, or be 64 or 128.
 /// - All the slices passed to the methods must be multiples of machine `long` size.
+#[derive(Debug, Clone)]
 pub struct GaloisField {
     w: CodeWord,
+    /// The field created by [`GaloisFieldBuilder`], or `None` for the default field of Jerasure.
+    field: Option<Arc<Field>>,
+}
+
+/// A field built by [`GaloisFieldBuilder`].
+///
+/// gf-complete writes the scratch memory of a field on some operations, such as the shift
+/// tables of [`MultType::Group`] and the lazy tables of the split region multiplications,
+/// which are also used by the default field of w=64. So each operation borrows a
+/// `gf_t` instance of its own from a pool, which creates another one when all are in use.
+struct Field {
+    params: GaloisFieldBuilder,
+    /// The polynomial as it is set, which the instances are created with, since gf-complete
+    /// cannot create the field of w = 31 with the polynomial it fills in.
+    init_poly: u64,
+    instances: Mutex<Vec<Instance>>,
+    /// Notified when an instance is returned to the pool.
+    returned: Condvar,
+}
+
+/// A field created by `gf_init_hard` with its own scratch memory and base field,
+/// which are freed on drop.
+struct Instance {
+    gf: gf_t,
+    /// The base field of a composite field, which `gf` points to.
+    _base: Option<Box<Instance>>,
+}
+
+// SAFETY: the instance exclusively owns the scratch memory and the base field it points to.
+unsafe impl Send for Instance {}
+
+/// Two fields are equal if they are of the same word size, and are both the default field
+/// of Jerasure, or are built with the same parameters, where the polynomial is compared
+/// as gf-complete uses it, e.g. `0x11d`, `0x1d` and the default one of w = 8 are the same.
+impl PartialEq for GaloisField {
+    fn eq(&self, other: &Self) -> bool {
+        self.w.to_u8() == other.w.to_u8()
+            && match (&self.field, &other.field) {
+                (None, None) => true,
+                (Some(a), Some(b)) => a.params == b.params,
+                _ => false,
+            }
+    }
+}
+
+impl Eq for GaloisField {}
+
+impl std::hash::Hash for GaloisField {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.w.hash(state);
+        self.field.as_ref().map(|field| &field.params).hash(state);
+    }
+}
+
+impl Field {
+    /// Runs `f` with an instance of the field no other thread is using.
+    ///
+    /// # Errors
+    /// [`Error::Other`] if all the instances are in use, and gf-complete cannot create
+    /// another one, e.g. when it runs out of memory.
+    fn try_with<R>(&self, f: impl FnOnce(&mut gf_t) -> R) -> Result<R, Error> {
+        let Some(instance) = self.take() else {
+            return Err(Error::other(format!(
+                "gf-complete cannot create another instance of the field {:?}",
+                self.params
+            )));
+        };
+        Ok(self.run(instance, f))
+    }
+
+    /// Same as [`try_with`](Self::try_with) for the operations that cannot fail, which wait
+    /// for an instance in use if gf-complete cannot create another one.
+    fn with<R>(&self, f: impl FnOnce(&mut gf_t) -> R) -> R {
+        let instance = self.take().unwrap_or_else(|| self.wait());
+        self.run(instance, f)
+    }
+
+    /// Takes an instance from the pool, or creates one if all are in use,
+    /// or returns `None` if gf-complete cannot create it.
+    fn take(&self) -> Option<Instance> {
+        let instance = self.lock().pop();
+        instance.or_else(|| Instance::new(&self.params, self.init_poly))
+    }
+
+    /// Waits for an instance to be returned to the pool, which ends since the instance
+    /// created by `build` is always returned.
+    fn wait(&self) -> Instance {
+        let mut instances = self.lock();
+        loop {
+            if let Some(instance) = instances.pop() {
+                return instance;
+            }
+            instances = self
+                .returned
+                .wait(instances)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+
+    /// Returns the polynomial gf-complete creates the field with, which is the default one
+    /// if none is set, without the leading bit of $x^w$.
+    ///
+    /// It is read from the arithmetic of the field: the lower terms of the polynomial are
+    /// $x^w = x^{w-1} \cdot x$, and the `s` of $x^2 + s x + 1$ of a composite field is the
+    /// upper half of $x \cdot x = s x + 1$.
+    fn resolved_poly(&self) -> u64 {
+        let w = u32::from(self.params.w.to_u8());
+        if w == 1 {
+            // x + 1 is the only polynomial of degree 1 with the root 1
+            return 1;
+        }
+        let (a, b, shift) = match self.params.mult_type {
+            MultType::Composite => (1_u128 << (w / 2), 1_u128 << (w / 2), w / 2),
+            _ => (1_u128 << (w - 1), 2, 0),
+        };
+        let product = self.with(|gf| match w {
+            64 => {
+                let multiply = unsafe { gf.multiply.w64 }.unwrap();
+                u128::from(unsafe { multiply(gf, a as u64, b as u64) })
+            }
+            128 => {
+                let multiply = unsafe { gf.multiply.w128 }.unwrap();
+                let (mut a, mut b, mut out) = (to_words(a), to_words(b), [0; 2]);
+                unsafe { multiply(gf, a.as_mut_ptr(), b.as_mut_ptr(), out.as_mut_ptr()) };
+                from_words(out)
+            }
+            _ => {
+                let multiply = unsafe { gf.multiply.w32 }.unwrap();
+                u128::from(unsafe { multiply(gf, a as u32, b as u32) })
+            }
+        });
+        (product >> shift) as u64
+    }
+
+    /// Runs `f` with `instance`, and returns it to the pool.
+    fn run<R>(&self, mut instance: Instance, f: impl FnOnce(&mut gf_t) -> R) -> R {
+        let ret = f(&mut instance.gf);
+        self.lock().push(instance);
+        self.returned.notify_one();
+        ret
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<Instance>> {
+        self.instances
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Instance {
+    /// Creates the field of `params` with the polynomial `prim_poly`,
+    /// or returns `None` if gf-complete cannot create it.
+    fn new(params: &GaloisFieldBuilder, prim_poly: u64) -> Option<Self> {
+        let mut base = match &params.base_field {
+            Some(base) => {
+                let base = base.field.as_ref()?;
+                Some(Box::new(Self::new(&base.params, base.init_poly)?))
+            }
+            None => None,
+        };
+        let base_gf = base
+            .as_mut()
+            .map_or(std::ptr::null_mut(), |base| &mut base.gf as *mut gf_t);
+        let mut gf = std::mem::MaybeUninit::<gf_t>::uninit();
+        let ret = unsafe {
+            jerasure_sys::gf_complete::gf_init_hard(
+                gf.as_mut_ptr(),
+                c_int::from(params.w.to_u8()),
+                params.mult_type.as_cint(),
+                params.region_type.0 as c_int,
+                params.divide_type.as_cint(),
+                prim_poly,
+                params.args.0,
+                params.args.1,
+                base_gf,
+                std::ptr::null_mut(),
+            )
+        };
+        if ret == 0 {
+            return None;
+        }
+        Some(Instance {
+            gf: unsafe { gf.assume_init() },
+            _base: base,
+        })
+    }
+}
+
+impl Drop for Instance {
+    fn drop(&mut self) {
+        unsafe {
+            jerasure_sys::gf_complete::gf_free(&mut self.gf, 0);
+        }
+    }
+}
+
+impl std::fmt::Debug for Field {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Field")
+            .field("params", &self.params)
+            .finish_non_exhaustive()
+    }
 }
 
 impl GaloisField {
     /// Creates a new GaloisField with the specified word size.
-    /// The word size must be in range 1..=32.
+    /// The word size must be in range 1..=32, or be 64 or 128.
+    ///
+    /// Jerasure only has the default fields for w in 1..=32, so the fields of 64 and
+    /// 128 bits are the default ones of gf-complete built by [`GaloisFieldBuilder`].
     pub fn try_from_code_word(w: CodeWord) -> Option<Self> {
         let w_u8 = w.to_u8();
+        if matches!(w_u8, 64 | 128) {
+            return GaloisFieldBuilder::new().w(w).build().ok();
+        }
         if w_u8 == 0 || w_u8 > 32 {
             return None;
         }
         unsafe { jerasure_sys::jerasure::galois_init_default_field(w_u8 as i32) };
-        Some(GaloisField { w })
+        Some(GaloisField { w, field: None })
     }
 
     /// Returns the word size of the GaloisField.
@@ -37,6 +256,76 @@ impl GaloisField {
         CodeWord::from_u8(self.w.to_u8())
     }
 
+    /// Returns the primitive polynomial of a field built by [`GaloisFieldBuilder`],
+    /// including the leading bit of $x^w$ for w < 64, which is the default polynomial
+    /// of gf-complete if none is set, or `None` for the default field of Jerasure.
+    ///
+    /// For a composite field, it is the `s` of $x^2 + s x + 1$.
+    pub fn prim_poly(&self) -> Option<u64> {
+        let field = self.field.as_ref()?;
+        let leading = match field.params.mult_type {
+            MultType::Composite => 0,
+            _ => 1_u64.checked_shl(u32::from(self.w.to_u8())).unwrap_or(0),
+        };
+        Some(field.params.prim_poly | leading)
+    }
+
+    /// Returns the base field of a composite field, or `None` for the other fields.
+    pub fn base_field(&self) -> Option<&GaloisField> {
+        self.field.as_ref()?.params.base_field.as_ref()
+    }
+
+    /// Returns whether the field is the default field of Jerasure,
+    /// which the coding routines of Jerasure compute in.
+    pub(crate) fn is_default(&self) -> bool {
+        self.field.is_none()
+    }
+
+    /// Returns the field of the word size `w`, which is 64 or 128.
+    fn wide_field(&self, w: CodeWord) -> &Field {
+        match &self.field {
+            Some(field) if self.w.to_u8() == w.to_u8() => field,
+            _ => panic!(
+                "the operation requires w({}), but the field has w({})",
+                w.to_u8(),
+                self.w.to_u8()
+            ),
+        }
+    }
+
+    /// Checks the regions of the word size `w`, which is 64 or 128, and returns the field.
+    fn check_wide_region(&self, w: CodeWord, src: &[u8], dest: &[u8]) -> Result<&Field, Error> {
+        let field: &Field = match &self.field {
+            Some(field) if self.w.to_u8() == w.to_u8() => field,
+            _ => {
+                return Err(Error::not_supported(format!(
+                    "the operation requires w({}), but the field has w({})",
+                    w.to_u8(),
+                    self.w.to_u8()
+                )));
+            }
+        };
+        if src.len() != dest.len() {
+            return Err(Error::invalid_arguments(format!(
+                "Input slices must be the same length: src.len({}) != dest.len({})",
+                src.len(),
+                dest.len()
+            )));
+        }
+        if !src.len().is_multiple_of(MACHINE_LONG_SIZE) {
+            return Err(Error::NotAligned(src.len()));
+        }
+        Ok(field)
+    }
+
+    /// Returns whether the regions are stored in the layout documented by [`region_multiply`](Self::region_multiply).
+    pub(crate) fn has_standard_regions(&self) -> bool {
+        self.field.as_ref().is_none_or(|field| {
+            !field.params.region_type.contains(RegionType::ALTMAP)
+                && !field.params.region_type.contains(RegionType::CAUCHY)
+        })
+    }
+
     /// Returns the inverse of `a` in the GF(2^w).
     /// # Example
     /// ```
@@ -46,7 +335,22 @@ impl GaloisField {
     /// assert_eq!(gf.inverse(142), 2);
     /// ```
     /// # Note: This is not the same as `1 / a` in normal arithmetic.
+    ///
+    /// For w in {64, 128}, `a` is zero-extended and the result is truncated
+    /// to the lower 32 bits, see [`inverse_u64`](Self::inverse_u64) and
+    /// [`inverse_u128`](Self::inverse_u128) for the whole elements.
     pub fn inverse(&self, a: i32) -> i32 {
+        match self.w {
+            CodeWord::W64 => return self.inverse_u64(u64::from(a as u32)) as i32,
+            CodeWord::W128 => return self.inverse_u128(u128::from(a as u32)) as i32,
+            _ => {}
+        }
+        if let Some(field) = &self.field {
+            return field.with(|gf| {
+                let inverse = unsafe { gf.inverse.w32 }.unwrap();
+                unsafe { inverse(gf, a as u32) }
+            }) as i32;
+        }
         unsafe { jerasure_sys::jerasure::galois_inverse(a, self.w.as_cint()) }
     }
 
@@ -72,7 +376,26 @@ impl GaloisField {
     /// assert_eq!(gf.multiply(24, 84), 179);
     /// ```
     /// # Note: This is not the same as `a * b` in normal arithmetic.
+    ///
+    /// For w in {64, 128}, `a` and `b` are zero-extended and the result is truncated
+    /// to the lower 32 bits, see [`multiply_u64`](Self::multiply_u64) and
+    /// [`multiply_u128`](Self::multiply_u128) for the whole elements.
     pub fn multiply(&self, a: i32, b: i32) -> i32 {
+        match self.w {
+            CodeWord::W64 => {
+                return self.multiply_u64(u64::from(a as u32), u64::from(b as u32)) as i32;
+            }
+            CodeWord::W128 => {
+                return self.multiply_u128(u128::from(a as u32), u128::from(b as u32)) as i32;
+            }
+            _ => {}
+        }
+        if let Some(field) = &self.field {
+            return field.with(|gf| {
+                let multiply = unsafe { gf.multiply.w32 }.unwrap();
+                unsafe { multiply(gf, a as u32, b as u32) }
+            }) as i32;
+        }
         unsafe { jerasure_sys::jerasure::galois_single_multiply(a, b, self.w.as_cint()) }
     }
 
@@ -85,10 +408,123 @@ impl GaloisField {
     /// assert_eq!(gf.divide(23, 74), 91);
     /// ```
     /// # Note: This is not the same as `a / b` in normal arithmetic.
+    ///
+    /// For w in {64, 128}, `a` and `b` are zero-extended and the result is truncated
+    /// to the lower 32 bits, see [`divide_u64`](Self::divide_u64) and
+    /// [`divide_u128`](Self::divide_u128) for the whole elements.
     pub fn divide(&self, a: i32, b: i32) -> i32 {
+        match self.w {
+            CodeWord::W64 => {
+                return self.divide_u64(u64::from(a as u32), u64::from(b as u32)) as i32;
+            }
+            CodeWord::W128 => {
+                return self.divide_u128(u128::from(a as u32), u128::from(b as u32)) as i32;
+            }
+            _ => {}
+        }
+        if let Some(field) = &self.field {
+            return field.with(|gf| {
+                let divide = unsafe { gf.divide.w32 }.unwrap();
+                unsafe { divide(gf, a as u32, b as u32) }
+            }) as i32;
+        }
         unsafe { jerasure_sys::jerasure::galois_single_divide(a, b, self.w.as_cint()) }
     }
 
+    /// Returns the inverse of `a` in the GF(2^64).
+    /// # Example
+    /// ```
+    /// # use jerasure_rs::galois::GaloisField;
+    /// # use jerasure_rs::CodeWord;
+    /// let gf = GaloisField::try_from_code_word(CodeWord::W64).unwrap();
+    /// let a = 0x0123_4567_89ab_cdef;
+    /// assert_eq!(gf.multiply_u64(a, gf.inverse_u64(a)), 1);
+    /// ```
+    ///
+    /// # Panics
+    /// If w is not 64.
+    pub fn inverse_u64(&self, a: u64) -> u64 {
+        let field = self.wide_field(CodeWord::W64);
+        field.with(|gf| {
+            let inverse = unsafe { gf.inverse.w64 }.unwrap();
+            unsafe { inverse(gf, a) }
+        })
+    }
+
+    /// Returns the result of `a * b` in the GF(2^64).
+    ///
+    /// # Panics
+    /// If w is not 64.
+    pub fn multiply_u64(&self, a: u64, b: u64) -> u64 {
+        let field = self.wide_field(CodeWord::W64);
+        field.with(|gf| {
+            let multiply = unsafe { gf.multiply.w64 }.unwrap();
+            unsafe { multiply(gf, a, b) }
+        })
+    }
+
+    /// Returns the result of `a / b` in the GF(2^64).
+    ///
+    /// # Panics
+    /// If w is not 64.
+    pub fn divide_u64(&self, a: u64, b: u64) -> u64 {
+        let field = self.wide_field(CodeWord::W64);
+        field.with(|gf| {
+            let divide = unsafe { gf.divide.w64 }.unwrap();
+            unsafe { divide(gf, a, b) }
+        })
+    }
+
+    /// Returns the inverse of `a` in the GF(2^128).
+    /// # Example
+    /// ```
+    /// # use jerasure_rs::galois::GaloisField;
+    /// # use jerasure_rs::CodeWord;
+    /// let gf = GaloisField::try_from_code_word(CodeWord::W128).unwrap();
+    /// let a = 0x0123_4567_89ab_cdef_fedc_ba98_7654_3210;
+    /// assert_eq!(gf.multiply_u128(a, gf.inverse_u128(a)), 1);
+    /// ```
+    ///
+    /// # Panics
+    /// If w is not 128.
+    pub fn inverse_u128(&self, a: u128) -> u128 {
+        let field = self.wide_field(CodeWord::W128);
+        let (mut a, mut out) = (to_words(a), [0; 2]);
+        field.with(|gf| {
+            let inverse = unsafe { gf.inverse.w128 }.unwrap();
+            unsafe { inverse(gf, a.as_mut_ptr(), out.as_mut_ptr()) }
+        });
+        from_words(out)
+    }
+
+    /// Returns the result of `a * b` in the GF(2^128).
+    ///
+    /// # Panics
+    /// If w is not 128.
+    pub fn multiply_u128(&self, a: u128, b: u128) -> u128 {
+        let field = self.wide_field(CodeWord::W128);
+        let (mut a, mut b, mut out) = (to_words(a), to_words(b), [0; 2]);
+        field.with(|gf| {
+            let multiply = unsafe { gf.multiply.w128 }.unwrap();
+            unsafe { multiply(gf, a.as_mut_ptr(), b.as_mut_ptr(), out.as_mut_ptr()) }
+        });
+        from_words(out)
+    }
+
+    /// Returns the result of `a / b` in the GF(2^128).
+    ///
+    /// # Panics
+    /// If w is not 128.
+    pub fn divide_u128(&self, a: u128, b: u128) -> u128 {
+        let field = self.wide_field(CodeWord::W128);
+        let (mut a, mut b, mut out) = (to_words(a), to_words(b), [0; 2]);
+        field.with(|gf| {
+            let divide = unsafe { gf.divide.w128 }.unwrap();
+            unsafe { divide(gf, a.as_mut_ptr(), b.as_mut_ptr(), out.as_mut_ptr()) }
+        });
+        from_words(out)
+    }
+
     /// Calculates the result of `a + b` in the GF(2^w) and stores it in `out`.
     ///
     /// That is, `out[i] = a[i] + b[i]`.
@@ -163,12 +599,28 @@ impl GaloisField {
         Ok(())
     }
 
-    /// Multiplies the `src` slice by `multiply_by` and adds `add` to each element, storing the result in `dest`.
+    /// Multiplies the `src` slice by `multiply_by`, storing the result in `dest`.
     ///
-    /// That is, `dest[i] = src[i] * multiply_by + add`.
+    /// That is, `dest[i] = src[i] * multiply_by` if `add` is zero, and
+    /// `dest[i] = src[i] * multiply_by + dest[i]` otherwise.
     ///
-    /// # Panics
-    /// It only works for word sizes of 8, 16, or 32 bits, otherwise it will panic.
+    /// Each element is stored in native byte order in 1 byte for w in 1..=8,
+    /// in 2 bytes for w in 9..=16, and in 4 bytes for w in 17..=32.
+    /// The bits of an element above w are ignored.
+    ///
+    /// The regions are multiplied by Jerasure, or by the field created by [`GaloisFieldBuilder`],
+    /// for w in {8, 16, 32}, and with the bit-matrix of `multiply_by` for the other word sizes
+    /// up to 32. For w in {64, 128}, it is the same as [`region_multiply_u64`](Self::region_multiply_u64)
+    /// and [`region_multiply_u128`](Self::region_multiply_u128) with `multiply_by` zero-extended.
+    /// So a field with a region technique other than [`RegionType::DEFAULT`] is only built
+    /// for w in {8, 16, 32, 64, 128}.
+    /// A field created with [`RegionType::ALTMAP`] or [`RegionType::CAUCHY`] stores the elements
+    /// of the regions in its own layout instead.
+    ///
+    /// # Errors
+    /// - [`Error::InvalidArguments`] if the slices are not the same length.
+    /// - [`Error::NotAligned`] if the length is not a multiple of the machine long size.
+    /// - [`Error::Other`] if gf-complete cannot create another instance of the field.
     pub fn region_multiply(
         &self,
         src: impl AsRef<[u8]>,
@@ -189,14 +641,34 @@ impl GaloisField {
         if n % MACHINE_LONG_SIZE != 0 {
             return Err(Error::NotAligned(n));
         }
+        if let (Some(field), CodeWord::W8 | CodeWord::W16 | CodeWord::W32) = (&self.field, self.w) {
+            return field.try_with(|gf| {
+                let multiply_region = unsafe { gf.multiply_region.w32 }.unwrap();
+                unsafe {
+                    multiply_region(
+                        gf,
+                        src.as_ptr() as *mut c_void,
+                        dest.as_mut_ptr() as *mut c_void,
+                        multiply_by as u32,
+                        n.try_into().unwrap(),
+                        add,
+                    );
+                }
+            });
+        }
         let mul_fn = match self.w {
             CodeWord::W8 => jerasure_sys::jerasure::galois_w08_region_multiply,
             CodeWord::W16 => jerasure_sys::jerasure::galois_w16_region_multiply,
             CodeWord::W32 => jerasure_sys::jerasure::galois_w32_region_multiply,
+            CodeWord::W64 => {
+                return self.region_multiply_u64(src, u64::from(multiply_by as u32), add, dest);
+            }
+            CodeWord::W128 => {
+                return self.region_multiply_u128(src, u128::from(multiply_by as u32), add, dest);
+            }
             CodeWord::Other(_) => {
-                return Err(Error::not_supported(
-                    "region multiply only supports w in {8, 16, 32}",
-                ));
+                self.region_multiply_bitmatrix(src, multiply_by, add != 0, dest);
+                return Ok(());
             }
         };
         let src_ptr = src.as_ptr() as *mut ::std::os::raw::c_char;
@@ -206,4 +678,594 @@ impl GaloisField {
         }
         Ok(())
     }
+
+    /// Multiplies the `src` slice by `multiply_by` in the GF(2^64), storing the result in `dest`.
+    ///
+    /// That is, `dest[i] = src[i] * multiply_by` if `add` is zero, and
+    /// `dest[i] = src[i] * multiply_by + dest[i]` otherwise,
+    /// where each element is stored in 8 bytes in native byte order.
+    ///
+    /// # Errors
+    /// - [`Error::NotSupported`] if w is not 64.
+    /// - [`Error::InvalidArguments`] if the slices are not the same length.
+    /// - [`Error::NotAligned`] if the length is not a multiple of the machine long size.
+    /// - [`Error::Other`] if gf-complete cannot create another instance of the field.
+    pub fn region_multiply_u64(
+        &self,
+        src: impl AsRef<[u8]>,
+        multiply_by: u64,
+        add: i32,
+        mut dest: impl AsMut<[u8]>,
+    ) -> Result<(), Error> {
+        let (src, dest) = (src.as_ref(), dest.as_mut());
+        let field = self.check_wide_region(CodeWord::W64, src, dest)?;
+        field.try_with(|gf| {
+            let multiply_region = unsafe { gf.multiply_region.w64 }.unwrap();
+            unsafe {
+                multiply_region(
+                    gf,
+                    src.as_ptr() as *mut c_void,
+                    dest.as_mut_ptr() as *mut c_void,
+                    multiply_by,
+                    src.len().try_into().unwrap(),
+                    add,
+                );
+            }
+        })
+    }
+
+    /// Multiplies the `src` slice by `multiply_by` in the GF(2^128), storing the result in `dest`.
+    ///
+    /// That is, `dest[i] = src[i] * multiply_by` if `add` is zero, and
+    /// `dest[i] = src[i] * multiply_by + dest[i]` otherwise,
+    /// where each element is stored in 16 bytes as two 8-byte words in native byte order,
+    /// the upper 64 bits followed by the lower 64 bits, which is the layout of gf-complete.
+    ///
+    /// # Errors
+    /// - [`Error::NotSupported`] if w is not 128.
+    /// - [`Error::InvalidArguments`] if the slices are not the same length,
+    ///   or the length is not a multiple of 16.
+    /// - [`Error::NotAligned`] if the length is not a multiple of the machine long size.
+    /// - [`Error::Other`] if gf-complete cannot create another instance of the field.
+    pub fn region_multiply_u128(
+        &self,
+        src: impl AsRef<[u8]>,
+        multiply_by: u128,
+        add: i32,
+        mut dest: impl AsMut<[u8]>,
+    ) -> Result<(), Error> {
+        let (src, dest) = (src.as_ref(), dest.as_mut());
+        let field = self.check_wide_region(CodeWord::W128, src, dest)?;
+        if !src.len().is_multiple_of(16) {
+            return Err(Error::invalid_arguments(format!(
+                "length({}) must be a multiple of the element size(16)",
+                src.len()
+            )));
+        }
+        let mut multiply_by = to_words(multiply_by);
+        field.try_with(|gf| {
+            let multiply_region = unsafe { gf.multiply_region.w128 }.unwrap();
+            unsafe {
+                multiply_region(
+                    gf,
+                    src.as_ptr() as *mut c_void,
+                    dest.as_mut_ptr() as *mut c_void,
+                    multiply_by.as_mut_ptr(),
+                    src.len().try_into().unwrap(),
+                    add,
+                );
+            }
+        })
+    }
+
+    /// Multiplies the `src` slice by `multiply_by` with the bit-matrix of `multiply_by`,
+    /// whose column `j` is `multiply_by * 2^j`, for the word sizes that Jerasure
+    /// has no region multiplication for.
+    fn region_multiply_bitmatrix(&self, src: &[u8], multiply_by: i32, add: bool, dest: &mut [u8]) {
+        let w = usize::from(self.w.to_u8());
+        let size = match w {
+            1..=8 => 1,
+            9..=16 => 2,
+            _ => 4,
+        };
+        let cols: Vec<u32> = (0..w)
+            .map(|j| self.multiply(multiply_by, (1_u32 << j) as i32) as u32)
+            .collect();
+        // `table[b][v]` is the product of an element whose byte `b` is `v` and the other bytes are 0
+        let table: Vec<Vec<u32>> = (0..size)
+            .map(|b| {
+                (0..256_usize)
+                    .map(|v| {
+                        (0..8)
+                            .filter(|i| (v >> i) & 1 == 1 && 8 * b + i < w)
+                            .fold(0, |acc, i| acc ^ cols[8 * b + i])
+                    })
+                    .collect()
+            })
+            .collect();
+        for (s, d) in src.chunks_exact(size).zip(dest.chunks_exact_mut(size)) {
+            let elem = match size {
+                1 => u32::from(s[0]),
+                2 => u32::from(u16::from_ne_bytes(s.try_into().unwrap())),
+                _ => u32::from_ne_bytes(s.try_into().unwrap()),
+            };
+            let mut prod = table.iter().enumerate().fold(0, |acc, (b, t)| {
+                acc ^ t[((elem >> (8 * b)) & 0xff) as usize]
+            });
+            if add {
+                prod ^= match size {
+                    1 => u32::from(d[0]),
+                    2 => u32::from(u16::from_ne_bytes((&*d).try_into().unwrap())),
+                    _ => u32::from_ne_bytes((&*d).try_into().unwrap()),
+                };
+            }
+            match size {
+                1 => d[0] = prod as u8,
+                2 => d.copy_from_slice(&(prod as u16).to_ne_bytes()),
+                _ => d.copy_from_slice(&prod.to_ne_bytes()),
+            }
+        }
+    }
+}
+
+/// The multiplication technique of a field created by [`GaloisFieldBuilder`].
+///
+/// For more information, see the manual of gf-complete.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum MultType {
+    #[default]
+    /// The default technique of gf-complete for the word size.
+    Default,
+    /// Shift-and-add multiplication, the slowest but simplest technique.
+    Shift,
+    /// Carry-free multiplication with the `PCLMUL` instruction.
+    ///
+    /// # Requires
+    /// - w must be in {4, 8, 16, 32}
+    /// - the CPU must support carry-free multiplication
+    CarryFree,
+    /// Carry-free multiplication with the reduction of Gopal and Kounavis.
+    ///
+    /// # Requires
+    /// - same as `CarryFree`
+    CarryFreeGk,
+    /// Multiplication by groups of `arg1` bits of `a` and `arg2` bits of the reduction.
+    ///
+    /// # Requires
+    /// - w must not be 4 or 8
+    /// - args must be positive and not greater than w
+    Group,
+    /// Multiplication by doubling the product, one bit of `b` at a time.
+    BytwoP,
+    /// Multiplication by doubling `a`, one bit of `b` at a time.
+    BytwoB,
+    /// Multiplication by a full multiplication table.
+    ///
+    /// # Requires
+    /// - w must be less than 15, or 16
+    Table,
+    /// Multiplication by log and anti-log tables.
+    ///
+    /// # Requires
+    /// - w must not be greater than 27
+    LogTable,
+    /// Log tables with an extended anti-log table that avoids checking for zero.
+    ///
+    /// # Requires
+    /// - w must be 8 or 16
+    LogZero,
+    /// Log tables with a doubly extended anti-log table.
+    ///
+    /// # Requires
+    /// - w must be 8
+    LogZeroExt,
+    /// Multiplication by tables of the products of `arg1` bits of `a` and `arg2` bits of `b`.
+    ///
+    /// # Requires
+    /// - args must be one of `8 4` for w = 8, `16 4`, `16 8` or `8 8` for w = 16,
+    ///   and `32 4`, `32 8`, `32 16` or `8 8` for w = 32
+    SplitTable,
+    /// Multiplication in the composite field GF((2^(w/2))^2), whose elements are the
+    /// polynomials $a_1 x + a_0$ over the base field set by [`GaloisFieldBuilder::base_field`],
+    /// modulo $x^2 + s x + 1$ with the `s` set by [`GaloisFieldBuilder::prim_poly`].
+    ///
+    /// # Requires
+    /// - w must be 8, 16, 32, 64 or 128, and the base field must be of w/2
+    /// - args must be `2 0`
+    /// - region technique must not be `SIMD`, `NOSIMD` or `CAUCHY`, and division technique
+    ///   must be `Default`
+    Composite,
+}
+
+impl MultType {
+    /// The value of `gf_mult_type_t`.
+    fn as_cint(self) -> c_int {
+        match self {
+            Self::Default => 0,
+            Self::Shift => 1,
+            Self::CarryFree => 2,
+            Self::CarryFreeGk => 3,
+            Self::Group => 4,
+            Self::BytwoP => 5,
+            Self::BytwoB => 6,
+            Self::Table => 7,
+            Self::LogTable => 8,
+            Self::LogZero => 9,
+            Self::LogZeroExt => 10,
+            Self::SplitTable => 11,
+            Self::Composite => 12,
+        }
+    }
+}
+
+/// The division technique of a field created by [`GaloisFieldBuilder`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum DivideType {
+    #[default]
+    /// The default technique of gf-complete for the multiplication technique.
+    Default,
+    /// Division by inverting the bit-matrix of the divisor.
+    ///
+    /// # Requires
+    /// - w must not be greater than 32
+    Matrix,
+    /// Division by the extended Euclidean algorithm.
+    Euclid,
+}
+
+impl DivideType {
+    /// The value of `gf_division_type_t`.
+    fn as_cint(self) -> c_int {
+        match self {
+            Self::Default => 0,
+            Self::Matrix => 1,
+            Self::Euclid => 2,
+        }
+    }
+}
+
+/// The flags of the region multiplication technique of a field created by [`GaloisFieldBuilder`],
+/// which can be combined with `|`.
+///
+/// The regions are only multiplied by gf-complete for w in {8, 16, 32, 64, 128},
+/// so the flags other than [`DEFAULT`](Self::DEFAULT) are not supported for the other word sizes.
+/// The lazy table flag of gf-complete is not supported either.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct RegionType(u32);
+
+impl RegionType {
+    /// The default technique of gf-complete for the multiplication technique.
+    pub const DEFAULT: Self = Self(0x0);
+    /// Multiply the regions two elements at a time with a table.
+    ///
+    /// # Requires
+    /// - multiplication technique must be `Table`, and w must be 8
+    pub const DOUBLE_TABLE: Self = Self(0x1);
+    /// Use the SIMD instructions.
+    pub const SIMD: Self = Self(0x8);
+    /// Do not use the SIMD instructions.
+    pub const NOSIMD: Self = Self(0x10);
+    /// Use an alternate memory layout of the elements in the regions.
+    pub const ALTMAP: Self = Self(0x20);
+    /// Multiply the regions as the bit-matrix of the Cauchy coding, with the elements
+    /// spread over w packets.
+    pub const CAUCHY: Self = Self(0x40);
+
+    /// Return whether all the flags of `other` are set.
+    pub fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for RegionType {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+/// The `GaloisFieldBuilder` is used to build a [`GaloisField`] with the techniques of gf-complete.
+///
+/// Unlike [`GaloisField::try_from_code_word`], which uses the default field of Jerasure
+/// for w up to 32, each built field owns its own gf-complete field, and all the methods
+/// of the `GaloisField` dispatch to it.
+///
+/// # Example
+/// ```
+/// # use jerasure_rs::galois::{GaloisField, GaloisFieldBuilder, MultType};
+/// # use jerasure_rs::CodeWord;
+/// let gf = GaloisFieldBuilder::new()
+///     .w(CodeWord::W16)
+///     .mult_type(MultType::SplitTable)
+///     .args(16, 4)
+///     .build()
+///     .unwrap();
+/// let default = GaloisField::try_from_code_word(CodeWord::W16).unwrap();
+/// assert_eq!(gf.multiply(1234, 5678), default.multiply(1234, 5678));
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct GaloisFieldBuilder {
+    w: CodeWord,
+    mult_type: MultType,
+    region_type: RegionType,
+    divide_type: DivideType,
+    prim_poly: u64,
+    args: (i32, i32),
+    base_field: Option<GaloisField>,
+}
+
+impl GaloisFieldBuilder {
+    /// Creates a new builder of the default field of `w = 8`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the word size, which must be in range 1..=32, or be 64 or 128.
+    pub fn w(mut self, w: CodeWord) -> Self {
+        self.w = CodeWord::from_u8(w.to_u8());
+        self
+    }
+
+    /// Sets the multiplication technique.
+    pub fn mult_type(mut self, mult_type: MultType) -> Self {
+        self.mult_type = mult_type;
+        self
+    }
+
+    /// Sets the region multiplication technique, which must be [`RegionType::DEFAULT`]
+    /// unless w is 8, 16, 32, 64 or 128.
+    pub fn region_type(mut self, region_type: RegionType) -> Self {
+        self.region_type = region_type;
+        self
+    }
+
+    /// Sets the division technique.
+    pub fn divide_type(mut self, divide_type: DivideType) -> Self {
+        self.divide_type = divide_type;
+        self
+    }
+
+    /// Sets the primitive polynomial, where the leading bit of $x^w$ is optional.
+    /// The default polynomial of gf-complete for the word size is used if it is 0.
+    ///
+    /// For w in {64, 128}, the leading bit does not fit and is omitted, and the polynomial
+    /// of GF(2^128) is given by its lower 64 bits, e.g. `0x87` for $x^{128} + x^7 + x^2 + x + 1$.
+    ///
+    /// For example, `0x11d` is the polynomial of GF(2^8) used by ISA-L,
+    /// which is also the default one of gf-complete.
+    ///
+    /// For [`MultType::Composite`], it is the element `s` of the base field in the
+    /// polynomial $x^2 + s x + 1$, which must be irreducible over the base field.
+    /// gf-complete only has the default `s` for some base fields, e.g. the default
+    /// field of w = 16 and the composite fields over it.
+    ///
+    /// gf-complete cannot create the field of w = 31 with a polynomial set,
+    /// since it overflows the leading bit, so only the default one is used for it.
+    pub fn prim_poly(mut self, prim_poly: u64) -> Self {
+        self.prim_poly = prim_poly;
+        self
+    }
+
+    /// Sets the arguments of the multiplication technique, which are required by
+    /// `Group` and `SplitTable`, and must be 0 otherwise.
+    pub fn args(mut self, arg1: i32, arg2: i32) -> Self {
+        self.args = (arg1, arg2);
+        self
+    }
+
+    /// Sets the base field of [`MultType::Composite`], which must be of w/2 and
+    /// built by `GaloisFieldBuilder`.
+    ///
+    /// # Example
+    /// ```
+    /// # use jerasure_rs::galois::{GaloisFieldBuilder, MultType};
+    /// # use jerasure_rs::CodeWord;
+    /// // GF((2^16)^2)
+    /// let base = GaloisFieldBuilder::new().w(CodeWord::W16).build().unwrap();
+    /// let gf = GaloisFieldBuilder::new()
+    ///     .w(CodeWord::W32)
+    ///     .mult_type(MultType::Composite)
+    ///     .args(2, 0)
+    ///     .base_field(base)
+    ///     .build()
+    ///     .unwrap();
+    /// let a = 0x1234_5678;
+    /// assert_eq!(gf.multiply(a, gf.inverse(a)), 1);
+    /// ```
+    pub fn base_field(mut self, base_field: GaloisField) -> Self {
+        self.base_field = Some(base_field);
+        self
+    }
+
+    /// Builds the `GaloisField`.
+    ///
+    /// The polynomial is filled in with the default one of gf-complete if it is not set,
+    /// and stored without the leading bit of $x^w$, so that the fields of the same
+    /// polynomial are equal however it is set.
+    ///
+    /// # Errors
+    /// - [`Error::NotSupported`] if w is not in range 1..=32, 64 or 128,
+    ///   or the region technique is not the default one for w not in {8, 16, 32, 64, 128}.
+    /// - [`Error::InvalidArguments`] if the polynomial is not a primitive polynomial of degree w,
+    ///   the base field is missing or not of w/2 for [`MultType::Composite`], or set otherwise,
+    ///   or gf-complete cannot create the field with the techniques, polynomial and arguments.
+    pub fn build(self) -> Result<GaloisField, Error> {
+        let w = self.w.to_u8();
+        if !(1..=32).contains(&w) && !matches!(w, 64 | 128) {
+            return Err(Error::not_supported(format!(
+                "w({w}) must be in 1..=32, 64 or 128"
+            )));
+        }
+        if self.region_type != RegionType::DEFAULT && !matches!(w, 8 | 16 | 32 | 64 | 128) {
+            return Err(Error::not_supported(format!(
+                "region technique({:?}) is only used for w in 8, 16, 32, 64 and 128, not w({w})",
+                self.region_type
+            )));
+        }
+        match (self.mult_type, &self.base_field) {
+            (MultType::Composite, None) => {
+                return Err(Error::invalid_arguments(
+                    "the composite field requires a base field",
+                ));
+            }
+            (MultType::Composite, Some(base)) => {
+                let base_w = base.w.to_u8();
+                if u16::from(base_w) * 2 != u16::from(w) {
+                    return Err(Error::invalid_arguments(format!(
+                        "the base field of w({base_w}) must be of w/2 for w({w})"
+                    )));
+                }
+                if base.field.is_none() {
+                    return Err(Error::invalid_arguments(
+                        "the base field must be built by GaloisFieldBuilder",
+                    ));
+                }
+                if self.prim_poly != 0 {
+                    check_composite_poly(base, self.prim_poly)?;
+                }
+            }
+            (_, Some(_)) => {
+                return Err(Error::invalid_arguments(format!(
+                    "the base field is only used by the composite field, not {:?}",
+                    self.mult_type
+                )));
+            }
+            (_, None) => {
+                if self.prim_poly != 0 {
+                    check_prim_poly(self.prim_poly, u32::from(w))?;
+                }
+            }
+        }
+        let Some(instance) = Instance::new(&self, self.prim_poly) else {
+            return Err(Error::invalid_arguments(format!(
+                "gf-complete cannot create the field {self:?}"
+            )));
+        };
+        let mut field = Field {
+            init_poly: self.prim_poly,
+            params: self,
+            instances: Mutex::new(vec![instance]),
+            returned: Condvar::new(),
+        };
+        // the fields of the same polynomial are equal however it is set
+        field.params.prim_poly = field.resolved_poly();
+        Ok(GaloisField {
+            w: field.params.w,
+            field: Some(Arc::new(field)),
+        })
+    }
+}
+
+/// Splits a 128-bit element into the upper and lower 64 bits, as gf-complete stores it.
+fn to_words(a: u128) -> [u64; 2] {
+    [(a >> 64) as u64, a as u64]
+}
+
+/// Joins the upper and lower 64 bits of a 128-bit element stored by gf-complete.
+fn from_words(words: [u64; 2]) -> u128 {
+    (u128::from(words[0]) << 64) | u128::from(words[1])
+}
+
+/// Checks that `poly` is a primitive polynomial of degree `w` over GF(2),
+/// where the leading bit of $x^w$ is optional, and omitted for w >= 64.
+fn check_prim_poly(poly: u64, w: u32) -> Result<(), Error> {
+    if w < 64 && poly >> (w + 1) != 0 {
+        return Err(Error::invalid_arguments(format!(
+            "polynomial({poly:#x}) must be of degree w({w})"
+        )));
+    }
+    // `x` generates the multiplicative group iff its order is 2^w - 1, which also implies
+    // that the polynomial is irreducible
+    let low = u128::from(poly) & (u128::MAX >> (128 - w));
+    let order = u128::MAX >> (128 - w);
+    let x = if w == 1 { low } else { 2 };
+    let pow = |e: u128| poly_pow_mod(x, e, low, w);
+    if pow(order) != 1 || prime_factors(order).any(|p| pow(order / p) == 1) {
+        return Err(Error::invalid_arguments(format!(
+            "polynomial({poly:#x}) is not primitive over GF(2), or not even irreducible"
+        )));
+    }
+    Ok(())
+}
+
+/// Checks that $x^2 + s x + 1$ is irreducible over the `base` field, where `s` is `poly`.
+///
+/// Substituting $x = s y$, it is irreducible iff $y^2 + y + 1 / s^2$ has no root,
+/// i.e. iff the trace of $1 / s^2$, which is also the trace of $1 / s$, is 1.
+fn check_composite_poly(base: &GaloisField, poly: u64) -> Result<(), Error> {
+    let w = base.w.to_u8();
+    let mask = 1_u64.checked_shl(u32::from(w)).unwrap_or(0).wrapping_sub(1);
+    if poly & !mask != 0 {
+        return Err(Error::invalid_arguments(format!(
+            "polynomial({poly:#x}) must be an element of the base field of w({w})"
+        )));
+    }
+    // the sum of the conjugates t, t^2, t^4, ..., t^(2^(w-1))
+    let trace = if w == 64 {
+        let t = base.inverse_u64(poly);
+        std::iter::successors(Some(t), |&a| Some(base.multiply_u64(a, a)))
+            .take(usize::from(w))
+            .fold(0, |acc, a| acc ^ a)
+    } else {
+        let t = base.inverse(poly as i32);
+        std::iter::successors(Some(t), |&a| Some(base.multiply(a, a)))
+            .take(usize::from(w))
+            .fold(0, |acc, a| acc ^ a) as u64
+    };
+    if trace != 1 {
+        return Err(Error::invalid_arguments(format!(
+            "polynomial(x^2 + {poly:#x}x + 1) is not irreducible over the base field of w({w})"
+        )));
+    }
+    Ok(())
+}
+
+/// Returns $a^e$ modulo the polynomial of degree `w` over GF(2) whose lower bits are `low`,
+/// where `a` is reduced.
+fn poly_pow_mod(a: u128, mut e: u128, low: u128, w: u32) -> u128 {
+    let mask = u128::MAX >> (128 - w);
+    let mul_mod = |mut a: u128, mut b: u128| {
+        let mut r = 0;
+        while b != 0 {
+            if b & 1 == 1 {
+                r ^= a;
+            }
+            b >>= 1;
+            let carry = (a >> (w - 1)) & 1 == 1;
+            a = (a << 1) & mask;
+            if carry {
+                a ^= low;
+            }
+        }
+        r
+    };
+    let (mut base, mut r) = (a, 1);
+    while e != 0 {
+        if e & 1 == 1 {
+            r = mul_mod(r, base);
+        }
+        base = mul_mod(base, base);
+        e >>= 1;
+    }
+    r
+}
+
+/// Returns the distinct prime factors of `n`.
+fn prime_factors(mut n: u128) -> impl Iterator<Item = u128> {
+    let mut factors = Vec::new();
+    let mut p = 2;
+    while p * p <= n {
+        if n.is_multiple_of(p) {
+            factors.push(p);
+            while n.is_multiple_of(p) {
+                n /= p;
+            }
+        }
+        p += if p == 2 { 1 } else { 2 };
+    }
+    if n > 1 {
+        factors.push(n);
+    }
+    factors.into_iter()
 }
@@ -1,5 +1,6 @@
 pub mod erasure;
 pub mod galois;
+pub mod matrix;
 
 const MACHINE_LONG_SIZE: usize = size_of::<std::os::raw::c_long>();
 
@@ -16,6 +17,12 @@ pub enum CodeWord {
     W16,
     /// A code word of 4 Bytes.
     W32,
+    /// A code word of 8 Bytes, only supported by the `u64` operations of
+    /// [`GaloisField`](galois::GaloisField).
+    W64,
+    /// A code word of 16 Bytes, only supported by the `u128` operations of
+    /// [`GaloisField`](galois::GaloisField).
+    W128,
     /// A code word of other size in bits.
     Other(u8),
 }
@@ -27,6 +34,8 @@ impl CodeWord {
             8 => Self::W8,
             16 => Self::W16,
             32 => Self::W32,
+            64 => Self::W64,
+            128 => Self::W128,
             _ => Self::Other(w),
         }
     }
@@ -37,6 +46,8 @@ impl CodeWord {
             Self::W8 => 8,
             Self::W16 => 16,
             Self::W32 => 32,
+            Self::W64 => 64,
+            Self::W128 => 128,
             Self::Other(w) => *w,
         }
     }
@@ -46,11 +57,28 @@ impl CodeWord {
             Self::W8 => 8,
             Self::W16 => 16,
             Self::W32 => 32,
+            Self::W64 => 64,
+            Self::W128 => 128,
             Self::Other(w) => *w as ::std::ffi::c_int,
         }
     }
 }
 
+/// Two code words are equal if they are of the same size, e.g. `W8` and `Other(8)`.
+impl PartialEq for CodeWord {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_u8() == other.to_u8()
+    }
+}
+
+impl Eq for CodeWord {}
+
+impl std::hash::Hash for CodeWord {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.to_u8().hash(state);
+    }
+}
+
 /// The `Error` enum defines the possible errors that this crate can occur.
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
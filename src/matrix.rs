@@ -0,0 +1,786 @@
+//! The `matrix` module provides matrices over Galois fields GF(2^w), and bit-matrices over GF(2).
+//!
+//! The matrices are owned by Rust and bound to a [`GaloisField`], so that custom coding
+//! matrices can be designed and verified before being passed to
+//! [`ErasureCodeBuilder::coding_matrix`](crate::erasure::ErasureCodeBuilder::coding_matrix)
+//! or [`ErasureCodeBuilder::coding_bitmatrix`](crate::erasure::ErasureCodeBuilder::coding_bitmatrix).
+//!
+//! The inversion and the conversion to bit-matrices are backed by the matrix routines
+//! of the Jerasure library, which compute in the default fields of Jerasure.
+//! They are computed in Rust for the fields built by
+//! [`GaloisFieldBuilder`](crate::galois::GaloisFieldBuilder).
+
+use ::std::os::raw::c_int;
+use std::fmt;
+
+use crate::Error;
+use crate::galois::GaloisField;
+
+use iter_tools::Itertools;
+
+/// The `Matrix` struct represents a matrix over the Galois field GF(2^w).
+///
+/// The elements are stored in row-major order, which is the same layout used by Jerasure.
+///
+/// # Example
+/// ```
+/// # use jerasure_rs::galois::GaloisField;
+/// # use jerasure_rs::matrix::Matrix;
+/// # use jerasure_rs::CodeWord;
+/// let gf = GaloisField::try_from_code_word(CodeWord::W8).unwrap();
+/// let mat = Matrix::vandermonde(gf.clone(), 3, 3).unwrap();
+/// let inv = mat.invert().unwrap();
+/// assert_eq!(mat.multiply(&inv).unwrap(), Matrix::identity(gf, 3).unwrap());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Matrix {
+    gf: GaloisField,
+    rows: usize,
+    cols: usize,
+    data: Vec<i32>,
+}
+
+/// Two matrices are equal if they are in the same field and have the same elements.
+impl PartialEq for Matrix {
+    fn eq(&self, other: &Self) -> bool {
+        self.gf == other.gf
+            && self.rows == other.rows
+            && self.cols == other.cols
+            && self.data == other.data
+    }
+}
+
+impl Eq for Matrix {}
+
+impl Matrix {
+    /// Creates a new matrix with `rows` rows and `cols` columns from the elements in row-major order.
+    ///
+    /// # Errors
+    /// - [`Error::NotSupported`] if w of the field is greater than 32.
+    /// - [`Error::InvalidArguments`] if the number of elements is not $rows \times cols$,
+    ///   or an element is not in the range $[0, 2^w)$.
+    pub fn new(
+        gf: GaloisField,
+        rows: usize,
+        cols: usize,
+        data: impl Into<Vec<i32>>,
+    ) -> Result<Self, Error> {
+        check_field(&gf)?;
+        let data = data.into();
+        if data.len() != rows * cols {
+            return Err(Error::invalid_arguments(format!(
+                "matrix of {rows}x{cols} must have {} elements, got {}",
+                rows * cols,
+                data.len()
+            )));
+        }
+        let bound = 1_i64 << gf.get_w().to_u8();
+        if let Some(&e) = data
+            .iter()
+            .find(|&&e| !(0..bound).contains(&i64::from(e as u32)))
+        {
+            return Err(Error::invalid_arguments(format!(
+                "element({}) must be in the range [0, 2^w({bound}))",
+                e as u32
+            )));
+        }
+        Ok(Self {
+            gf,
+            rows,
+            cols,
+            data,
+        })
+    }
+
+    /// Creates a matrix of zeros with `rows` rows and `cols` columns.
+    ///
+    /// # Errors
+    /// - [`Error::NotSupported`] if w of the field is greater than 32.
+    pub fn zeros(gf: GaloisField, rows: usize, cols: usize) -> Result<Self, Error> {
+        check_field(&gf)?;
+        Ok(Self {
+            gf,
+            rows,
+            cols,
+            data: vec![0; rows * cols],
+        })
+    }
+
+    /// Creates an identity matrix of size `n`.
+    ///
+    /// # Errors
+    /// - [`Error::NotSupported`] if w of the field is greater than 32.
+    pub fn identity(gf: GaloisField, n: usize) -> Result<Self, Error> {
+        let mut mat = Self::zeros(gf, n, n)?;
+        (0..n).for_each(|i| mat.data[i * n + i] = 1);
+        Ok(mat)
+    }
+
+    /// Creates a Vandermonde matrix with `rows` rows and `cols` columns,
+    /// whose element $(i, j)$ is $i^j$ in GF(2^w), with $0^0 = 1$.
+    ///
+    /// Any `cols` rows of the matrix are linearly independent.
+    ///
+    /// # Errors
+    /// - [`Error::NotSupported`] if w of the field is greater than 32.
+    /// - [`Error::InvalidArguments`] if `rows` is greater than $2^w$,
+    ///   since the rows would not be distinct.
+    pub fn vandermonde(gf: GaloisField, rows: usize, cols: usize) -> Result<Self, Error> {
+        check_field(&gf)?;
+        let w = gf.get_w().to_u8();
+        if rows as u64 > 1_u64 << w {
+            return Err(Error::invalid_arguments(format!(
+                "rows({rows}) of a vandermonde matrix must be less or equal than 2^w({})",
+                1_u64 << w
+            )));
+        }
+        let mut mat = Self::zeros(gf, rows, cols)?;
+        for i in 0..rows {
+            let mut e = 1;
+            for j in 0..cols {
+                mat.data[i * cols + j] = e;
+                e = mat.gf.multiply(e, i as i32);
+            }
+        }
+        Ok(mat)
+    }
+
+    /// Creates a Cauchy matrix with `x.len()` rows and `y.len()` columns,
+    /// whose element $(i, j)$ is $1 / (x_i + y_j)$ in GF(2^w).
+    ///
+    /// All the square submatrices of a Cauchy matrix are invertible.
+    ///
+    /// # Errors
+    /// - [`Error::NotSupported`] if w of the field is greater than 32.
+    /// - [`Error::InvalidArguments`] if the elements of `x` and `y` are not distinct,
+    ///   or not in the range $[0, 2^w)$.
+    pub fn cauchy(gf: GaloisField, x: &[i32], y: &[i32]) -> Result<Self, Error> {
+        check_field(&gf)?;
+        let bound = 1_i64 << gf.get_w().to_u8();
+        if let Some(&e) = x
+            .iter()
+            .chain(y)
+            .find(|&&e| !(0..bound).contains(&i64::from(e as u32)))
+        {
+            return Err(Error::invalid_arguments(format!(
+                "element({}) of x and y must be in the range [0, 2^w({bound}))",
+                e as u32
+            )));
+        }
+        if !x.iter().chain(y).all_unique() {
+            return Err(Error::invalid_arguments(
+                "elements of x and y must be distinct",
+            ));
+        }
+        let data = x
+            .iter()
+            .cartesian_product(y)
+            .map(|(&xi, &yj)| gf.inverse(gf.add(xi, yj)))
+            .collect();
+        Ok(Self {
+            gf,
+            rows: x.len(),
+            cols: y.len(),
+            data,
+        })
+    }
+
+    /// Returns the Galois field of the matrix.
+    pub fn gf(&self) -> &GaloisField {
+        &self.gf
+    }
+
+    /// Returns the word size of the Galois field in bits.
+    pub fn w(&self) -> u8 {
+        self.gf.get_w().to_u8()
+    }
+
+    /// Returns the number of rows.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Returns the number of columns.
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Returns the elements in row-major order.
+    pub fn as_slice(&self) -> &[i32] {
+        &self.data
+    }
+
+    /// Consumes the matrix and returns the elements in row-major order.
+    pub fn into_vec(self) -> Vec<i32> {
+        self.data
+    }
+
+    /// Returns the element $(i, j)$.
+    ///
+    /// # Panics
+    /// Panics if `i` or `j` is out of bounds.
+    pub fn get(&self, i: usize, j: usize) -> i32 {
+        assert!(i < self.rows && j < self.cols, "index out of bounds");
+        self.data[i * self.cols + j]
+    }
+
+    /// Returns the row `i`.
+    ///
+    /// # Panics
+    /// Panics if `i` is out of bounds.
+    pub fn row(&self, i: usize) -> &[i32] {
+        &self.data[i * self.cols..(i + 1) * self.cols]
+    }
+
+    /// Returns the product `self * rhs`.
+    ///
+    /// # Errors
+    /// - [`Error::InvalidArguments`] if the matrices are in different fields,
+    ///   or the number of columns of `self` is not the number of rows of `rhs`.
+    pub fn multiply(&self, rhs: &Matrix) -> Result<Matrix, Error> {
+        if self.gf != rhs.gf {
+            return Err(Error::invalid_arguments(format!(
+                "matrices must be in the same field: {:?} != {:?}",
+                self.gf, rhs.gf
+            )));
+        }
+        if self.cols != rhs.rows {
+            return Err(Error::invalid_arguments(format!(
+                "cannot multiply a {}x{} matrix by a {}x{} matrix",
+                self.rows, self.cols, rhs.rows, rhs.cols
+            )));
+        }
+        let mut out = Self::zeros(self.gf.clone(), self.rows, rhs.cols)?;
+        for i in 0..self.rows {
+            for j in 0..rhs.cols {
+                out.data[i * rhs.cols + j] = (0..self.cols).fold(0, |acc, l| {
+                    self.gf
+                        .add(acc, self.gf.multiply(self.get(i, l), rhs.get(l, j)))
+                });
+            }
+        }
+        Ok(out)
+    }
+
+    /// Returns the transpose of the matrix.
+    pub fn transpose(&self) -> Matrix {
+        Self {
+            gf: self.gf.clone(),
+            rows: self.cols,
+            cols: self.rows,
+            data: (0..self.cols)
+                .flat_map(|j| (0..self.rows).map(move |i| self.get(i, j)))
+                .collect(),
+        }
+    }
+
+    /// Returns the inverse of the matrix.
+    ///
+    /// # Errors
+    /// - [`Error::InvalidArguments`] if the matrix is not square, or is singular.
+    pub fn invert(&self) -> Result<Matrix, Error> {
+        self.check_square()?;
+        if !self.gf.is_default() {
+            return self.gauss_jordan_invert();
+        }
+        let mut mat = self.data.clone();
+        let mut inv = vec![0; self.data.len()];
+        let ret = unsafe {
+            jerasure_sys::jerasure::jerasure_invert_matrix(
+                mat.as_mut_ptr(),
+                inv.as_mut_ptr(),
+                self.rows as c_int,
+                c_int::from(self.w()),
+            )
+        };
+        if ret != 0 {
+            return Err(Error::invalid_arguments("matrix is singular"));
+        }
+        Ok(Self {
+            gf: self.gf.clone(),
+            rows: self.rows,
+            cols: self.cols,
+            data: inv,
+        })
+    }
+
+    /// Returns whether the matrix is square and invertible.
+    pub fn is_invertible(&self) -> bool {
+        if self.rows != self.cols {
+            return false;
+        }
+        if !self.gf.is_default() {
+            return self.rank() == self.rows;
+        }
+        let mut mat = self.data.clone();
+        unsafe {
+            jerasure_sys::jerasure::jerasure_invertible_matrix(
+                mat.as_mut_ptr(),
+                self.rows as c_int,
+                c_int::from(self.w()),
+            ) != 0
+        }
+    }
+
+    /// Returns the rank of the matrix.
+    pub fn rank(&self) -> usize {
+        let (_, rank) = self.eliminate();
+        rank
+    }
+
+    /// Returns the determinant of the matrix.
+    ///
+    /// # Errors
+    /// - [`Error::InvalidArguments`] if the matrix is not square.
+    pub fn det(&self) -> Result<i32, Error> {
+        self.check_square()?;
+        let (echelon, rank) = self.eliminate();
+        if rank < self.rows {
+            return Ok(0);
+        }
+        // row swaps do not change the sign in characteristic 2
+        Ok((0..self.rows).fold(1, |acc, i| self.gf.multiply(acc, echelon.get(i, i))))
+    }
+
+    /// Returns the submatrix with the rows `rows` and the columns `cols`, in the given order.
+    ///
+    /// # Errors
+    /// - [`Error::InvalidArguments`] if an index is out of bounds.
+    pub fn submatrix(&self, rows: &[usize], cols: &[usize]) -> Result<Matrix, Error> {
+        if let Some(i) = rows.iter().find(|&&i| i >= self.rows) {
+            return Err(Error::invalid_arguments(format!(
+                "row index({i}) out of bounds({})",
+                self.rows
+            )));
+        }
+        if let Some(j) = cols.iter().find(|&&j| j >= self.cols) {
+            return Err(Error::invalid_arguments(format!(
+                "column index({j}) out of bounds({})",
+                self.cols
+            )));
+        }
+        let data = rows
+            .iter()
+            .cartesian_product(cols)
+            .map(|(&i, &j)| self.get(i, j))
+            .collect();
+        Ok(Self {
+            gf: self.gf.clone(),
+            rows: rows.len(),
+            cols: cols.len(),
+            data,
+        })
+    }
+
+    fn check_square(&self) -> Result<(), Error> {
+        if self.rows != self.cols {
+            return Err(Error::invalid_arguments(format!(
+                "matrix must be square, got {}x{}",
+                self.rows, self.cols
+            )));
+        }
+        Ok(())
+    }
+
+    /// Inverts the square matrix by Gauss-Jordan elimination with the operations of the field.
+    fn gauss_jordan_invert(&self) -> Result<Matrix, Error> {
+        let n = self.rows;
+        let mut mat = self.clone();
+        let mut inv = Self::identity(self.gf.clone(), n)?;
+        for j in 0..n {
+            let Some(pivot) = (j..n).find(|&i| mat.get(i, j) != 0) else {
+                return Err(Error::invalid_arguments("matrix is singular"));
+            };
+            for l in 0..n {
+                mat.data.swap(j * n + l, pivot * n + l);
+                inv.data.swap(j * n + l, pivot * n + l);
+            }
+            let p = mat.get(j, j);
+            for l in 0..n {
+                mat.data[j * n + l] = self.gf.divide(mat.get(j, l), p);
+                inv.data[j * n + l] = self.gf.divide(inv.get(j, l), p);
+            }
+            for i in (0..n).filter(|&i| i != j) {
+                let factor = mat.get(i, j);
+                if factor == 0 {
+                    continue;
+                }
+                for l in 0..n {
+                    let sub = self.gf.multiply(factor, mat.get(j, l));
+                    mat.data[i * n + l] = self.gf.add(mat.get(i, l), sub);
+                    let sub = self.gf.multiply(factor, inv.get(j, l));
+                    inv.data[i * n + l] = self.gf.add(inv.get(i, l), sub);
+                }
+            }
+        }
+        Ok(inv)
+    }
+
+    /// Reduces the matrix to a row echelon form by Gaussian elimination,
+    /// and returns the echelon form and the rank.
+    ///
+    /// For a full rank square matrix, the pivots are on the diagonal.
+    fn eliminate(&self) -> (Matrix, usize) {
+        let mut mat = self.clone();
+        let cols = self.cols;
+        let mut rank = 0;
+        for j in 0..cols {
+            let Some(pivot) = (rank..self.rows).find(|&i| mat.get(i, j) != 0) else {
+                continue;
+            };
+            for l in 0..cols {
+                mat.data.swap(rank * cols + l, pivot * cols + l);
+            }
+            let p = mat.get(rank, j);
+            for i in rank + 1..self.rows {
+                let e = mat.get(i, j);
+                if e == 0 {
+                    continue;
+                }
+                let factor = self.gf.divide(e, p);
+                for l in j..cols {
+                    let sub = self.gf.multiply(factor, mat.get(rank, l));
+                    mat.data[i * cols + l] = self.gf.add(mat.get(i, l), sub);
+                }
+            }
+            rank += 1;
+        }
+        (mat, rank)
+    }
+}
+
+/// Checks that the elements of the field fit in `i32`, i.e. w is not greater than 32.
+fn check_field(gf: &GaloisField) -> Result<(), Error> {
+    let w = gf.get_w().to_u8();
+    if w > 32 {
+        return Err(Error::not_supported(format!(
+            "w({w}) of a matrix must not be greater than 32"
+        )));
+    }
+    Ok(())
+}
+
+impl fmt::Display for Matrix {
+    /// Prints the matrix in the same format as `jerasure_print_matrix`,
+    /// with the columns right-aligned to the width of the largest element in GF(2^w).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = (u64::MAX >> (64 - self.w())).to_string().len();
+        for i in 0..self.rows {
+            let row = self
+                .row(i)
+                .iter()
+                .map(|&e| format!("{:>width$}", e as u32))
+                .join(" ");
+            writeln!(f, "{row}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The `BitMatrix` struct represents a bit-matrix over GF(2), made of $w \times w$ blocks.
+///
+/// A matrix over GF(2^w) with `r` rows and `c` columns is converted to a bit-matrix with
+/// $r \times w$ rows and $c \times w$ columns, where each element is replaced by the
+/// $w \times w$ bit-matrix of the multiplication by the element.
+/// Coding with a bit-matrix only takes XOR operations, so the number of ones
+/// determines the coding performance.
+///
+/// # Example
+/// ```
+/// # use jerasure_rs::galois::GaloisField;
+/// # use jerasure_rs::matrix::{BitMatrix, Matrix};
+/// # use jerasure_rs::CodeWord;
+/// let gf = GaloisField::try_from_code_word(CodeWord::Other(3)).unwrap();
+/// let mat = Matrix::new(gf, 1, 2, [1, 2]).unwrap();
+/// let bmat = BitMatrix::from(&mat);
+/// assert_eq!(bmat.rows(), 3);
+/// assert_eq!(bmat.cols(), 6);
+/// assert_eq!(bmat.n_ones(), 7);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitMatrix {
+    w: u8,
+    rows: usize,
+    cols: usize,
+    data: Vec<i32>,
+}
+
+impl From<&Matrix> for BitMatrix {
+    fn from(mat: &Matrix) -> Self {
+        let w = mat.w();
+        let (rows, cols) = (mat.rows * usize::from(w), mat.cols * usize::from(w));
+        if rows * cols == 0 {
+            return Self::zeros(w, rows, cols);
+        }
+        if !mat.gf.is_default() {
+            // the column `c` of a block is the element multiplied by x^c
+            let mut bmat = Self::zeros(w, rows, cols);
+            let w = usize::from(w);
+            for (i, j) in (0..mat.rows).cartesian_product(0..mat.cols) {
+                for c in 0..w {
+                    let prod = mat.gf.multiply(mat.get(i, j), 1 << c) as u32;
+                    for r in 0..w {
+                        bmat.data[(i * w + r) * cols + j * w + c] = ((prod >> r) & 1) as i32;
+                    }
+                }
+            }
+            return bmat;
+        }
+        let mut elems = mat.data.clone();
+        let data = unsafe {
+            let ptr = jerasure_sys::jerasure::jerasure_matrix_to_bitmatrix(
+                mat.cols as c_int,
+                mat.rows as c_int,
+                c_int::from(w),
+                elems.as_mut_ptr(),
+            );
+            assert!(!ptr.is_null(), "Failed to allocate bit matrix");
+            let data = std::slice::from_raw_parts(ptr, rows * cols).to_vec();
+            jerasure_sys::jerasure::jerasure_free_matrix(ptr);
+            data
+        };
+        Self {
+            w,
+            rows,
+            cols,
+            data,
+        }
+    }
+}
+
+impl BitMatrix {
+    /// Creates a new bit-matrix with `rows` rows and `cols` columns from the bits in row-major order,
+    /// made of $w \times w$ blocks.
+    ///
+    /// # Errors
+    /// - [`Error::InvalidArguments`] if `w` is not in 1..=32, `rows` or `cols` is not a multiple
+    ///   of `w`, the number of elements is not $rows \times cols$, or an element is not 0 or 1.
+    pub fn new(w: u8, rows: usize, cols: usize, data: impl Into<Vec<i32>>) -> Result<Self, Error> {
+        let data = data.into();
+        if !(1..=32).contains(&w) {
+            return Err(Error::invalid_arguments(format!(
+                "w({w}) must be in 1..=32"
+            )));
+        }
+        let wu = usize::from(w);
+        if !rows.is_multiple_of(wu) || !cols.is_multiple_of(wu) {
+            return Err(Error::invalid_arguments(format!(
+                "rows({rows}) and cols({cols}) must be multiples of w({w})"
+            )));
+        }
+        if data.len() != rows * cols {
+            return Err(Error::invalid_arguments(format!(
+                "bit-matrix of {rows}x{cols} must have {} elements, got {}",
+                rows * cols,
+                data.len()
+            )));
+        }
+        if let Some(e) = data.iter().find(|&&e| e != 0 && e != 1) {
+            return Err(Error::invalid_arguments(format!(
+                "element({e}) of a bit-matrix must be 0 or 1"
+            )));
+        }
+        Ok(Self {
+            w,
+            rows,
+            cols,
+            data,
+        })
+    }
+
+    /// Creates a bit-matrix of zeros with `rows` rows and `cols` columns.
+    fn zeros(w: u8, rows: usize, cols: usize) -> Self {
+        Self {
+            w,
+            rows,
+            cols,
+            data: vec![0; rows * cols],
+        }
+    }
+
+    /// Creates an identity bit-matrix with $n \times w$ rows and columns.
+    pub fn identity(w: u8, n: usize) -> Self {
+        let n = n * usize::from(w);
+        let mut bmat = Self::zeros(w, n, n);
+        (0..n).for_each(|i| bmat.data[i * n + i] = 1);
+        bmat
+    }
+
+    /// Returns the size of the blocks in bits.
+    pub fn w(&self) -> u8 {
+        self.w
+    }
+
+    /// Returns the number of rows in bits.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Returns the number of columns in bits.
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Returns the bits in row-major order.
+    pub fn as_slice(&self) -> &[i32] {
+        &self.data
+    }
+
+    /// Consumes the bit-matrix and returns the bits in row-major order.
+    pub fn into_vec(self) -> Vec<i32> {
+        self.data
+    }
+
+    /// Returns the bit $(i, j)$.
+    ///
+    /// # Panics
+    /// Panics if `i` or `j` is out of bounds.
+    pub fn get(&self, i: usize, j: usize) -> i32 {
+        assert!(i < self.rows && j < self.cols, "index out of bounds");
+        self.data[i * self.cols + j]
+    }
+
+    /// Returns the number of ones in the bit-matrix.
+    pub fn n_ones(&self) -> usize {
+        self.data.iter().filter(|&&e| e == 1).count()
+    }
+
+    /// Returns the number of XOR operations to compute the product by a vector of packets,
+    /// that is, the number of ones minus one in each non-zero row.
+    pub fn n_xors(&self) -> usize {
+        self.data
+            .chunks(self.cols.max(1))
+            .map(|row| row.iter().filter(|&&e| e == 1).count().saturating_sub(1))
+            .sum()
+    }
+
+    /// Converts the bit-matrix back to a matrix over `gf`.
+    ///
+    /// # Errors
+    /// - [`Error::InvalidArguments`] if `w` of `gf` is not the block size,
+    ///   or a block is not the bit-matrix of an element in GF(2^w).
+    pub fn to_matrix(&self, gf: GaloisField) -> Result<Matrix, Error> {
+        let w = usize::from(self.w);
+        if gf.get_w().to_u8() != self.w {
+            return Err(Error::invalid_arguments(format!(
+                "w({}) of the field must be the block size({w})",
+                gf.get_w().to_u8()
+            )));
+        }
+        let (rows, cols) = (self.rows / w, self.cols / w);
+        // the first column of a block is the binary representation of the element
+        let data = (0..rows)
+            .cartesian_product(0..cols)
+            .map(|(i, j)| {
+                (0..w).fold(0_u32, |acc, b| {
+                    acc | ((self.get(i * w + b, j * w) as u32) << b)
+                }) as i32
+            })
+            .collect::<Vec<_>>();
+        let mat = Matrix {
+            gf,
+            rows,
+            cols,
+            data,
+        };
+        if BitMatrix::from(&mat) != *self {
+            return Err(Error::invalid_arguments(
+                "bit-matrix is not converted from a matrix over GF(2^w)",
+            ));
+        }
+        Ok(mat)
+    }
+
+    /// Returns the product `self * rhs` over GF(2).
+    ///
+    /// # Errors
+    /// - [`Error::InvalidArguments`] if the block sizes are different,
+    ///   or the number of columns of `self` is not the number of rows of `rhs`.
+    pub fn multiply(&self, rhs: &BitMatrix) -> Result<BitMatrix, Error> {
+        if self.w != rhs.w {
+            return Err(Error::invalid_arguments(format!(
+                "bit-matrices must have the same block size: w({}) != w({})",
+                self.w, rhs.w
+            )));
+        }
+        if self.cols != rhs.rows {
+            return Err(Error::invalid_arguments(format!(
+                "cannot multiply a {}x{} bit-matrix by a {}x{} bit-matrix",
+                self.rows, self.cols, rhs.rows, rhs.cols
+            )));
+        }
+        let mut out = Self::zeros(self.w, self.rows, rhs.cols);
+        for i in 0..self.rows {
+            for l in (0..self.cols).filter(|&l| self.get(i, l) == 1) {
+                for j in 0..rhs.cols {
+                    out.data[i * rhs.cols + j] ^= rhs.get(l, j);
+                }
+            }
+        }
+        Ok(out)
+    }
+
+    /// Returns the inverse of the bit-matrix.
+    ///
+    /// # Errors
+    /// - [`Error::InvalidArguments`] if the bit-matrix is not square, or is singular.
+    pub fn invert(&self) -> Result<BitMatrix, Error> {
+        if self.rows != self.cols {
+            return Err(Error::invalid_arguments(format!(
+                "bit-matrix must be square, got {}x{}",
+                self.rows, self.cols
+            )));
+        }
+        let mut mat = self.data.clone();
+        let mut inv = vec![0; self.data.len()];
+        let ret = unsafe {
+            jerasure_sys::jerasure::jerasure_invert_bitmatrix(
+                mat.as_mut_ptr(),
+                inv.as_mut_ptr(),
+                self.rows as c_int,
+            )
+        };
+        if ret != 0 {
+            return Err(Error::invalid_arguments("bit-matrix is singular"));
+        }
+        Ok(Self {
+            w: self.w,
+            rows: self.rows,
+            cols: self.cols,
+            data: inv,
+        })
+    }
+
+    /// Returns whether the bit-matrix is square and invertible.
+    pub fn is_invertible(&self) -> bool {
+        if self.rows != self.cols {
+            return false;
+        }
+        let mut mat = self.data.clone();
+        unsafe {
+            jerasure_sys::jerasure::jerasure_invertible_bitmatrix(
+                mat.as_mut_ptr(),
+                self.rows as c_int,
+            ) != 0
+        }
+    }
+}
+
+impl fmt::Display for BitMatrix {
+    /// Prints the bit-matrix in the same format as `jerasure_print_bitmatrix`,
+    /// with the $w \times w$ blocks separated by spaces and blank lines.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let w = usize::from(self.w);
+        for i in 0..self.rows {
+            if i != 0 && i % w == 0 {
+                writeln!(f)?;
+            }
+            let row = self.data[i * self.cols..(i + 1) * self.cols]
+                .chunks(w)
+                .map(|block| block.iter().join(""))
+                .join(" ");
+            writeln!(f, "{row}")?;
+        }
+        Ok(())
+    }
+}
@@ -1,5 +1,6 @@
 use std::num::NonZeroI32;
 
+use iter_tools::Itertools;
 use jerasure_rs::erasure::CodingMethod;
 use rand::seq::SliceRandom;
 
@@ -55,6 +56,63 @@ fn reed_sol() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+#[test]
+fn reed_sol_r6() -> Result<(), Box<dyn std::error::Error>> {
+    use jerasure_rs::erasure::Technique;
+    let method = CodingMethod::ReedSolR6;
+
+    // test matrix
+    test_matrix(4, 2, method)?;
+    for w in [
+        jerasure_rs::CodeWord::W8,
+        jerasure_rs::CodeWord::W16,
+        jerasure_rs::CodeWord::W32,
+    ] {
+        let ec = jerasure_rs::erasure::ErasureCodeBuilder::new()
+            .coding_method(method)
+            .k(NonZeroI32::new(6).unwrap())
+            .m(NonZeroI32::new(2).unwrap())
+            .w(w)
+            .tech(Technique::Matrix)
+            .build()?;
+        // the first parity is the XOR of the data
+        let data = make_rand_blk(6, 256);
+        let mut code = make_zero_blk(2, 256);
+        ec.encode(&data, &mut code)?;
+        let xor = data.iter().fold(vec![0_u8; 256], |acc, d| {
+            acc.iter().zip(d).map(|(a, b)| a ^ b).collect()
+        });
+        assert_eq!(code[0], xor);
+        double_erasure_test(ec)?;
+    }
+
+    // the rest tech not supported
+    for tech in [
+        Technique::BitMatrix,
+        Technique::Schedule,
+        Technique::ScheduleCache,
+    ] {
+        let ec = jerasure_rs::erasure::ErasureCodeBuilder::new()
+            .coding_method(method)
+            .k(NonZeroI32::new(4).unwrap())
+            .m(NonZeroI32::new(2).unwrap())
+            .packet_size(NonZeroI32::new(128).unwrap())
+            .tech(tech)
+            .build();
+        assert!(matches!(ec, Err(jerasure_rs::Error::NotSupported(_))));
+    }
+    // m != 2
+    let ec = jerasure_rs::erasure::ErasureCodeBuilder::new()
+        .coding_method(method)
+        .k(NonZeroI32::new(4).unwrap())
+        .m(NonZeroI32::new(3).unwrap())
+        .tech(Technique::Matrix)
+        .build();
+    assert!(matches!(ec, Err(jerasure_rs::Error::NotSupported(_))));
+
+    Ok(())
+}
+
 #[test]
 fn cauchy() -> Result<(), Box<dyn std::error::Error>> {
     let k = 4;
@@ -67,9 +125,1874 @@ fn cauchy() -> Result<(), Box<dyn std::error::Error>> {
     // schedule
     test_sechdule(k, m, method)?;
 
-    // fail test
-    fail_test(k, m, method);
+    // fail test
+    fail_test(k, m, method);
+
+    Ok(())
+}
+
+#[test]
+fn cauchy_orig_xy() -> Result<(), Box<dyn std::error::Error>> {
+    use jerasure_rs::erasure::{ErasureCodeBuilder, Technique};
+    let k = 4;
+    let m = 2;
+    let method = CodingMethod::CauchyOrig;
+    test_matrix(k, m, method)?;
+    test_bitmatrix(k, m, method)?;
+    test_sechdule(k, m, method)?;
+    fail_test(k, m, method);
+
+    // the original cauchy matrix is the XY matrix with X = {0..m} and Y = {m..m+k}
+    let data = make_rand_blk(k as usize, 1 << 12);
+    for tech in [
+        Technique::Matrix,
+        Technique::BitMatrix,
+        Technique::Schedule,
+        Technique::ScheduleCache,
+    ] {
+        let builder = ErasureCodeBuilder::new()
+            .k(NonZeroI32::new(k).unwrap())
+            .m(NonZeroI32::new(m).unwrap())
+            .packet_size(NonZeroI32::new(128).unwrap())
+            .tech(tech);
+        let orig = builder.clone().coding_method(method).build()?;
+        let xy = builder
+            .clone()
+            .coding_method(CodingMethod::CauchyXY)
+            .cauchy_xy(Vec::from_iter(0..m), Vec::from_iter(m..m + k))
+            .build()?;
+        let good = builder.coding_method(CodingMethod::Cauchy).build()?;
+        let mut orig_code = make_zero_blk(m as usize, 1 << 12);
+        let mut xy_code = make_zero_blk(m as usize, 1 << 12);
+        orig.encode(&data, &mut orig_code)?;
+        xy.encode(&data, &mut xy_code)?;
+        assert_eq!(orig_code, xy_code);
+        assert_eq!(orig.n_ones(), xy.n_ones());
+        // the optimized cauchy matrix has no more ones than the original one
+        assert!(good.n_ones() <= orig.n_ones());
+        double_erasure_test(xy)?;
+    }
+
+    // arbitrary X and Y sets
+    let ec = ErasureCodeBuilder::new()
+        .coding_method(CodingMethod::CauchyXY)
+        .k(NonZeroI32::new(k).unwrap())
+        .m(NonZeroI32::new(m).unwrap())
+        .cauchy_xy([17, 200], [3, 99, 42, 255])
+        .tech(Technique::Matrix)
+        .build()?;
+    general_test(ec)?;
+
+    // invalid X and Y sets
+    for (x, y) in [
+        (vec![0], vec![2, 3, 4, 5]),
+        (vec![0, 1], vec![2, 3, 4]),
+        (vec![0, 1], vec![2, 3, 4, 1]),
+        (vec![0, 1], vec![2, 3, 4, 256]),
+        (vec![-1, 1], vec![2, 3, 4, 5]),
+    ] {
+        let ec = ErasureCodeBuilder::new()
+            .coding_method(CodingMethod::CauchyXY)
+            .k(NonZeroI32::new(k).unwrap())
+            .m(NonZeroI32::new(m).unwrap())
+            .cauchy_xy(x, y)
+            .tech(Technique::Matrix)
+            .build();
+        assert!(matches!(ec, Err(jerasure_rs::Error::InvalidArguments(_))));
+    }
+    // missing X and Y sets
+    let ec = ErasureCodeBuilder::new()
+        .coding_method(CodingMethod::CauchyXY)
+        .k(NonZeroI32::new(k).unwrap())
+        .m(NonZeroI32::new(m).unwrap())
+        .tech(Technique::Matrix)
+        .build();
+    assert!(matches!(ec, Err(jerasure_rs::Error::InvalidArguments(_))));
+
+    Ok(())
+}
+
+/// The Cauchy matrix used by ISA-L, i.e. `gf_gen_cauchy1_matrix` without the identity part.
+fn isal_cauchy_matrix(k: i32, m: i32, w: jerasure_rs::CodeWord) -> Vec<i32> {
+    let Some(gf) = jerasure_rs::galois::GaloisField::try_from_code_word(w) else {
+        return vec![];
+    };
+    (k..k + m)
+        .flat_map(|i| (0..k).map(move |j| (i, j)))
+        .map(|(i, j)| gf.inverse(i ^ j))
+        .collect()
+}
+
+#[test]
+fn custom() -> Result<(), Box<dyn std::error::Error>> {
+    use jerasure_rs::erasure::{ErasureCodeBuilder, Technique};
+    let k = 4;
+    let m = 3;
+    let method = CodingMethod::Custom;
+    let builder = || {
+        ErasureCodeBuilder::new()
+            .coding_method(method)
+            .k(NonZeroI32::new(k).unwrap())
+            .m(NonZeroI32::new(m).unwrap())
+    };
+
+    // the ISA-L cauchy matrix is the XY matrix with X = {k..k+m} and Y = {0..k}
+    let data = make_rand_blk(k as usize, 1 << 12);
+    for w in [
+        jerasure_rs::CodeWord::W8,
+        jerasure_rs::CodeWord::W16,
+        jerasure_rs::CodeWord::W32,
+    ] {
+        let matrix = isal_cauchy_matrix(k, m, w);
+        for tech in [Technique::Matrix, Technique::BitMatrix, Technique::Schedule] {
+            let custom = builder()
+                .w(w)
+                .packet_size(NonZeroI32::new(64).unwrap())
+                .coding_matrix(matrix.clone())
+                .tech(tech)
+                .build()?;
+            let xy = builder()
+                .w(w)
+                .packet_size(NonZeroI32::new(64).unwrap())
+                .coding_method(CodingMethod::CauchyXY)
+                .cauchy_xy(Vec::from_iter(k..k + m), Vec::from_iter(0..k))
+                .tech(tech)
+                .build()?;
+            let blk_size = (1 << 12) * w.to_u8() as usize / 8;
+            let data = make_rand_blk(k as usize, blk_size);
+            let mut custom_code = make_zero_blk(m as usize, blk_size);
+            let mut xy_code = make_zero_blk(m as usize, blk_size);
+            custom.encode(&data, &mut custom_code)?;
+            xy.encode(&data, &mut xy_code)?;
+            assert_eq!(custom_code, xy_code, "w:{w:?} tech:{tech:?}");
+            double_erasure_test(custom)?;
+        }
+    }
+
+    // a bit-matrix of a single XOR parity
+    let w = 4;
+    let bitmatrix =
+        Vec::from_iter((0..w).flat_map(|r| (0..k * w).map(move |c| i32::from(c % w == r))));
+    for tech in [
+        Technique::BitMatrix,
+        Technique::Schedule,
+        Technique::ScheduleCache,
+    ] {
+        let ec = builder()
+            .m(NonZeroI32::new(1).unwrap())
+            .w(jerasure_rs::CodeWord::Other(w as u8))
+            .packet_size(NonZeroI32::new(64).unwrap())
+            .coding_bitmatrix(bitmatrix.clone())
+            .tech(tech)
+            .build();
+        let ec = ec?;
+        let mut code = make_zero_blk(1, 1 << 12);
+        ec.encode(&data, &mut code)?;
+        let xor = data.iter().fold(vec![0_u8; 1 << 12], |acc, d| {
+            acc.iter().zip(d).map(|(a, b)| a ^ b).collect()
+        });
+        assert_eq!(code[0], xor);
+        general_test(ec)?;
+    }
+    let ec = builder()
+        .m(NonZeroI32::new(1).unwrap())
+        .w(jerasure_rs::CodeWord::Other(w as u8))
+        .coding_bitmatrix(bitmatrix.clone())
+        .tech(Technique::Matrix)
+        .build();
+    assert!(matches!(ec, Err(jerasure_rs::Error::NotSupported(_))));
+
+    // invalid matrices
+    let matrix = isal_cauchy_matrix(k, m, jerasure_rs::CodeWord::W8);
+    let mut zero = matrix.clone();
+    zero[5] = 0;
+    let mut dup = matrix.clone();
+    dup.copy_within(0..k as usize, k as usize);
+    let mut out_of_range = matrix.clone();
+    out_of_range[0] = 256;
+    for invalid in [
+        vec![],
+        matrix[1..].to_vec(),
+        zero,
+        dup,
+        out_of_range,
+        vec![1; (k * m) as usize],
+    ] {
+        let ec = builder()
+            .coding_matrix(invalid.clone())
+            .tech(Technique::Matrix)
+            .build();
+        assert!(
+            matches!(ec, Err(jerasure_rs::Error::InvalidArguments(_))),
+            "matrix:{invalid:?} res:{:?}",
+            ec.err()
+        );
+    }
+    let mut not_binary = bitmatrix.clone();
+    not_binary[0] = 2;
+    let mut singular = bitmatrix.clone();
+    singular[0] = 0;
+    for invalid in [bitmatrix[1..].to_vec(), not_binary, singular] {
+        let ec = builder()
+            .m(NonZeroI32::new(1).unwrap())
+            .w(jerasure_rs::CodeWord::Other(w as u8))
+            .packet_size(NonZeroI32::new(64).unwrap())
+            .coding_bitmatrix(invalid)
+            .tech(Technique::BitMatrix)
+            .build();
+        assert!(matches!(ec, Err(jerasure_rs::Error::InvalidArguments(_))));
+    }
+    // missing matrix
+    let ec = builder().tech(Technique::Matrix).build();
+    assert!(matches!(ec, Err(jerasure_rs::Error::InvalidArguments(_))));
+    // too many erasure patterns to check that the matrix is MDS
+    for (k, m, expect_ok) in [(9, 9, true), (10, 9, false), (71, 3, true), (72, 3, false)] {
+        let ec = builder()
+            .k(NonZeroI32::new(k).unwrap())
+            .m(NonZeroI32::new(m).unwrap())
+            .coding_matrix(isal_cauchy_matrix(k, m, jerasure_rs::CodeWord::W8))
+            .tech(Technique::Matrix)
+            .build();
+        match expect_ok {
+            true => assert!(ec.is_ok(), "k:{k} m:{m}"),
+            false => assert!(
+                matches!(ec, Err(jerasure_rs::Error::NotSupported(_))),
+                "k:{k} m:{m}"
+            ),
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn coding_matrix() -> Result<(), Box<dyn std::error::Error>> {
+    use jerasure_rs::erasure::{ErasureCodeBuilder, Technique};
+    let k = 5;
+    let m = 2;
+    let builder = |method: CodingMethod, tech: Technique| {
+        ErasureCodeBuilder::new()
+            .coding_method(method)
+            .k(NonZeroI32::new(k).unwrap())
+            .m(NonZeroI32::new(m).unwrap())
+            .w(jerasure_rs::CodeWord::Other(7))
+            .packet_size(NonZeroI32::new(64).unwrap())
+            .tech(tech)
+    };
+
+    // RAID-6: P is the XOR of the data, and Q has the coefficients 2^j
+    let ec = builder(CodingMethod::ReedSolR6, Technique::Matrix)
+        .w(jerasure_rs::CodeWord::W8)
+        .build()?;
+    assert_eq!(
+        ec.coding_matrix(),
+        Some(vec![1, 1, 1, 1, 1, 1, 2, 4, 8, 16])
+    );
+    assert_eq!(ec.coding_bitmatrix(), None);
+
+    for method in [
+        CodingMethod::Cauchy,
+        CodingMethod::CauchyOrig,
+        CodingMethod::Liberation,
+        CodingMethod::BlaumRoth,
+    ] {
+        for tech in [
+            Technique::BitMatrix,
+            Technique::Schedule,
+            Technique::ScheduleCache,
+        ] {
+            let w = if matches!(method, CodingMethod::BlaumRoth) {
+                6
+            } else {
+                7
+            };
+            let ec = builder(method, tech)
+                .w(jerasure_rs::CodeWord::Other(w))
+                .build()?;
+            let bitmatrix = ec.coding_bitmatrix().unwrap();
+            let w = usize::from(w);
+            assert_eq!(bitmatrix.len(), (k * m) as usize * w * w);
+            assert_eq!(bitmatrix.iter().filter(|&&e| e == 1).count(), ec.n_ones());
+            let mut rebuilt = vec![
+                builder(CodingMethod::Custom, tech)
+                    .w(ec.w())
+                    .coding_bitmatrix(bitmatrix)
+                    .build()?,
+            ];
+            match ec.coding_matrix() {
+                Some(matrix) => {
+                    assert_eq!(matrix.len(), (k * m) as usize);
+                    rebuilt.push(
+                        builder(CodingMethod::Custom, tech)
+                            .w(ec.w())
+                            .coding_matrix(matrix)
+                            .build()?,
+                    );
+                }
+                None => assert!(!matches!(
+                    method,
+                    CodingMethod::Cauchy | CodingMethod::CauchyOrig
+                )),
+            }
+
+            // the rebuilt codes are the same as the original one
+            let blk_size = 64 * w * 8;
+            let data = make_rand_blk(k as usize, blk_size);
+            let mut code = make_zero_blk(m as usize, blk_size);
+            ec.encode(&data, &mut code)?;
+            for rebuilt in rebuilt {
+                assert_eq!(rebuilt.coding_bitmatrix(), ec.coding_bitmatrix());
+                let mut rebuilt_code = make_zero_blk(m as usize, blk_size);
+                rebuilt.encode(&data, &mut rebuilt_code)?;
+                assert_eq!(rebuilt_code, code, "method:{method:?} tech:{tech:?}");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn decoding_matrix() -> Result<(), Box<dyn std::error::Error>> {
+    use jerasure_rs::erasure::{ErasureCodeBuilder, Technique};
+    let (k, m) = (5, 3);
+    let blk_size = 1 << 12;
+    for (method, tech) in [
+        (CodingMethod::ReedSolVand, Technique::Matrix),
+        (CodingMethod::Cauchy, Technique::Matrix),
+        (CodingMethod::Cauchy, Technique::BitMatrix),
+        (CodingMethod::Cauchy, Technique::Schedule),
+        (CodingMethod::CauchyOrig, Technique::BitMatrix),
+    ] {
+        let ec = ErasureCodeBuilder::new()
+            .coding_method(method)
+            .k(NonZeroI32::new(k).unwrap())
+            .m(NonZeroI32::new(m).unwrap())
+            .packet_size(NonZeroI32::new(64).unwrap())
+            .tech(tech)
+            .build()?;
+        // erasing a coding device only needs the coding matrix
+        let dm = ec.decoding_matrix(&[k])?;
+        assert_eq!(dm.erased(), &[k]);
+        assert_eq!(dm.survivors(), Vec::from_iter(0..k));
+        assert_eq!(dm.is_bitmatrix(), !matches!(tech, Technique::Matrix));
+        match dm.is_bitmatrix() {
+            true => assert_eq!(
+                dm.rows(),
+                &ec.coding_bitmatrix().unwrap()[..dm.rows().len()]
+            ),
+            false => assert_eq!(dm.rows(), &ec.coding_matrix().unwrap()[..k as usize]),
+        }
+
+        // every erasure pattern of up to m devices, reused across stripes
+        let stripes = (0..3)
+            .map(|_| {
+                let data = make_rand_blk(k as usize, blk_size);
+                let mut code = make_zero_blk(m as usize, blk_size);
+                ec.encode(&data, &mut code).map(|_| (data, code))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        for n in 0..=m {
+            for erased in (0..k + m).combinations(n as usize) {
+                let dm = ec.decoding_matrix(&erased)?;
+                for (data, code) in &stripes {
+                    let mut erased_data = data.clone();
+                    let mut erased_code = code.clone();
+                    for &idx in &erased {
+                        if idx < k {
+                            erased_data[idx as usize].fill(0);
+                        } else {
+                            erased_code[(idx - k) as usize].fill(0);
+                        }
+                    }
+                    ec.decode_with_matrix(&dm, &mut erased_data, &mut erased_code)?;
+                    assert_eq!(&erased_data, data, "method:{method:?} erased:{erased:?}");
+                    assert_eq!(&erased_code, code, "method:{method:?} erased:{erased:?}");
+                }
+            }
+        }
+
+        // invalid erasures
+        assert!(matches!(
+            ec.decoding_matrix(&[0, 1, 2, 3]),
+            Err(jerasure_rs::Error::TooManyErasure(4, 3))
+        ));
+        assert!(matches!(
+            ec.decoding_matrix(&[k + m]),
+            Err(jerasure_rs::Error::InvalidArguments(_))
+        ));
+    }
+
+    // a decoding matrix of another code
+    let ec = |k: i32, tech: Technique| {
+        ErasureCodeBuilder::new()
+            .coding_method(CodingMethod::Cauchy)
+            .k(NonZeroI32::new(k).unwrap())
+            .m(NonZeroI32::new(m).unwrap())
+            .packet_size(NonZeroI32::new(64).unwrap())
+            .tech(tech)
+            .build()
+    };
+    let dm = ec(k, Technique::Matrix)?.decoding_matrix(&[0])?;
+    // the same shape, but another coding method or another field
+    let same_shape = |method: CodingMethod| {
+        ErasureCodeBuilder::new()
+            .coding_method(method)
+            .k(NonZeroI32::new(k).unwrap())
+            .m(NonZeroI32::new(m).unwrap())
+            .tech(Technique::Matrix)
+    };
+    let other_field = jerasure_rs::galois::GaloisFieldBuilder::new()
+        .prim_poly(0x12d)
+        .build()?;
+    for other in [
+        ec(k, Technique::BitMatrix)?,
+        ec(k + 1, Technique::Matrix)?,
+        same_shape(CodingMethod::ReedSolVand).build()?,
+        same_shape(CodingMethod::CauchyOrig).build()?,
+        same_shape(CodingMethod::Cauchy)
+            .galois_field(other_field)
+            .build()?,
+    ] {
+        let mut data = make_zero_blk(other.k() as usize, blk_size);
+        let mut code = make_zero_blk(m as usize, blk_size);
+        let res = other.decode_with_matrix(&dm, &mut data, &mut code);
+        assert!(matches!(res, Err(jerasure_rs::Error::InvalidArguments(_))));
+    }
+
+    // the fields of the same polynomial, with or without the leading bit, are the same field
+    let field = |poly: u64| {
+        jerasure_rs::galois::GaloisFieldBuilder::new()
+            .prim_poly(poly)
+            .build()
+    };
+    let dm = same_shape(CodingMethod::Cauchy)
+        .galois_field(field(0)?)
+        .build()?
+        .decoding_matrix(&[0])?;
+    for poly in [0x1d, 0x11d] {
+        let other = same_shape(CodingMethod::Cauchy)
+            .galois_field(field(poly)?)
+            .build()?;
+        let data = make_rand_blk(k as usize, blk_size);
+        let mut code = make_zero_blk(m as usize, blk_size);
+        other.encode(&data, &mut code)?;
+        let mut erased_data = data.clone();
+        erased_data[0].fill(0);
+        other.decode_with_matrix(&dm, &mut erased_data, &mut code)?;
+        assert_eq!(erased_data, data);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn decoding_cache() -> Result<(), Box<dyn std::error::Error>> {
+    use jerasure_rs::erasure::{DecodingCacheStats, ErasureCodeBuilder, Technique};
+    use std::num::NonZeroUsize;
+    let (k, m) = (4, 3);
+    let blk_size = 1 << 12;
+    let builder = |tech: Technique| {
+        ErasureCodeBuilder::new()
+            .coding_method(CodingMethod::Cauchy)
+            .k(NonZeroI32::new(k).unwrap())
+            .m(NonZeroI32::new(m).unwrap())
+            .packet_size(NonZeroI32::new(64).unwrap())
+            .tech(tech)
+    };
+    assert_eq!(
+        builder(Technique::Matrix).build()?.decoding_cache_stats(),
+        None
+    );
+
+    let decode = |ec: &jerasure_rs::erasure::ErasureCode, erased: &[i32]| {
+        let data = make_rand_blk(k as usize, blk_size);
+        let mut code = make_zero_blk(ec.m() as usize, blk_size);
+        ec.encode(&data, &mut code).unwrap();
+        let mut erased_data = data.clone();
+        let mut erased_code = code.clone();
+        for &idx in erased {
+            if idx < k {
+                erased_data[idx as usize].fill(0);
+            } else {
+                erased_code[(idx - k) as usize].fill(0);
+            }
+        }
+        ec.decode(&mut erased_data, &mut erased_code, erased)
+            .unwrap();
+        assert_eq!(erased_data, data, "erased:{erased:?}");
+        assert_eq!(erased_code, code, "erased:{erased:?}");
+    };
+    for tech in [
+        Technique::Matrix,
+        Technique::BitMatrix,
+        Technique::Schedule,
+        Technique::ScheduleCache,
+    ] {
+        let ec = builder(tech)
+            .decoding_cache(NonZeroUsize::new(2).unwrap())
+            .build()?;
+        // the erasure pattern is sorted and deduplicated
+        decode(&ec, &[1, 0]);
+        decode(&ec, &[0, 1, 1]);
+        decode(&ec, &[k]);
+        decode(&ec, &[2, k + 1]);
+        // [0, 1] is evicted
+        decode(&ec, &[0, 1]);
+        assert_eq!(
+            ec.decoding_cache_stats(),
+            Some(DecodingCacheStats {
+                hits: 1,
+                misses: 4,
+                len: 2,
+                capacity: 2,
+            })
+        );
+        // too many erasures are not cached
+        let mut data = make_zero_blk(k as usize, blk_size);
+        let mut code = make_zero_blk(m as usize, blk_size);
+        let res = ec.decode(&mut data, &mut code, &[0, 1, 2, 3]);
+        assert!(matches!(res, Err(jerasure_rs::Error::TooManyErasure(_, _))));
+        assert_eq!(ec.decoding_cache_stats().unwrap().misses, 4);
+    }
+
+    // the cache is shared between threads
+    let ec = std::sync::Arc::new(
+        builder(Technique::BitMatrix)
+            .decoding_cache(NonZeroUsize::new(16).unwrap())
+            .build()?,
+    );
+    std::thread::scope(|s| {
+        for t in 0..4 {
+            let ec = std::sync::Arc::clone(&ec);
+            s.spawn(move || {
+                for i in 0..8 {
+                    decode(&ec, &[(t + i) % (k + m)]);
+                }
+            });
+        }
+    });
+    let stats = ec.decoding_cache_stats().unwrap();
+    assert_eq!(stats.hits + stats.misses, 32);
+    assert_eq!(stats.len, (k + m) as usize);
+    assert!(stats.misses >= (k + m) as u64);
+
+    Ok(())
+}
+
+#[test]
+fn schedule_cache() -> Result<(), Box<dyn std::error::Error>> {
+    use jerasure_rs::erasure::{ErasureCodeBuilder, Technique};
+    // every erasure pattern of a 10+4 code, decoded twice to hit the cached schedules
+    let (k, m) = (10, 4);
+    let ec = ErasureCodeBuilder::new()
+        .coding_method(CodingMethod::Cauchy)
+        .k(NonZeroI32::new(k).unwrap())
+        .m(NonZeroI32::new(m).unwrap())
+        .packet_size(NonZeroI32::new(8).unwrap())
+        .tech(Technique::ScheduleCache)
+        .build()?;
+    let blk_size = 8 * 8 * 4;
+    let data = make_rand_blk(k as usize, blk_size);
+    let mut code = make_zero_blk(m as usize, blk_size);
+    ec.encode(&data, &mut code)?;
+    for _ in 0..2 {
+        for n in 0..=m {
+            for erased in (0..k + m).combinations(n as usize) {
+                let mut erased_data = data.clone();
+                let mut erased_code = code.clone();
+                for &idx in &erased {
+                    if idx < k {
+                        erased_data[idx as usize].fill(0);
+                    } else {
+                        erased_code[(idx - k) as usize].fill(0);
+                    }
+                }
+                ec.decode(&mut erased_data, &mut erased_code, &erased)?;
+                assert_eq!(erased_data, data, "erased:{erased:?}");
+                assert_eq!(erased_code, code, "erased:{erased:?}");
+            }
+        }
+    }
+    // the same encoding as the schedule technique
+    let schedule = ErasureCodeBuilder::new()
+        .coding_method(CodingMethod::Cauchy)
+        .k(NonZeroI32::new(k).unwrap())
+        .m(NonZeroI32::new(m).unwrap())
+        .packet_size(NonZeroI32::new(8).unwrap())
+        .tech(Technique::Schedule)
+        .build()?;
+    let mut schedule_code = make_zero_blk(m as usize, blk_size);
+    schedule.encode(&data, &mut schedule_code)?;
+    assert_eq!(schedule_code, code);
+
+    // m = 1
+    let ec = ErasureCodeBuilder::new()
+        .coding_method(CodingMethod::Cauchy)
+        .k(NonZeroI32::new(k).unwrap())
+        .m(NonZeroI32::new(1).unwrap())
+        .packet_size(NonZeroI32::new(8).unwrap())
+        .tech(Technique::ScheduleCache)
+        .build()?;
+    general_test(ec)?;
+
+    Ok(())
+}
+
+#[test]
+fn decode_partial() -> Result<(), Box<dyn std::error::Error>> {
+    use jerasure_rs::erasure::{ErasureCodeBuilder, Technique};
+    use std::num::NonZeroUsize;
+    let (k, m) = (6, 3);
+    let blk_size = 1 << 12;
+    for tech in [
+        Technique::Matrix,
+        Technique::BitMatrix,
+        Technique::Schedule,
+        Technique::ScheduleCache,
+    ] {
+        for cache in [None, NonZeroUsize::new(4)] {
+            let mut builder = ErasureCodeBuilder::new()
+                .coding_method(CodingMethod::Cauchy)
+                .k(NonZeroI32::new(k).unwrap())
+                .m(NonZeroI32::new(m).unwrap())
+                .packet_size(NonZeroI32::new(64).unwrap())
+                .tech(tech);
+            if let Some(cache) = cache {
+                builder = builder.decoding_cache(cache);
+            }
+            let ec = builder.build()?;
+            let data = make_rand_blk(k as usize, blk_size);
+            let mut code = make_zero_blk(m as usize, blk_size);
+            ec.encode(&data, &mut code)?;
+            for erased in (0..k + m).combinations(m as usize) {
+                for targets in erased.iter().copied().powerset() {
+                    let mut erased_data = data.clone();
+                    let mut erased_code = code.clone();
+                    for &idx in &erased {
+                        if idx < k {
+                            erased_data[idx as usize].fill(0xaa);
+                        } else {
+                            erased_code[(idx - k) as usize].fill(0xaa);
+                        }
+                    }
+                    ec.decode_partial(&mut erased_data, &mut erased_code, &erased, &targets)?;
+                    // only the targets are recovered
+                    for idx in 0..k + m {
+                        let (actual, expect) = match idx < k {
+                            true => (&erased_data[idx as usize], &data[idx as usize]),
+                            false => (&erased_code[(idx - k) as usize], &code[(idx - k) as usize]),
+                        };
+                        if !erased.contains(&idx) || targets.contains(&idx) {
+                            assert_eq!(actual, expect, "erased:{erased:?} targets:{targets:?}");
+                        } else {
+                            assert!(actual.iter().all(|&b| b == 0xaa));
+                        }
+                    }
+                }
+            }
+            if let Some(stats) = ec.decoding_cache_stats() {
+                assert_eq!(stats.len, 4);
+                assert!(stats.hits > 0);
+            }
+
+            // a target not erased
+            let mut erased_data = data.clone();
+            let mut erased_code = code.clone();
+            let res = ec.decode_partial(&mut erased_data, &mut erased_code, &[0, 1], &[2]);
+            assert!(matches!(res, Err(jerasure_rs::Error::InvalidArguments(_))));
+            // too many erasures
+            let res = ec.decode_partial(&mut erased_data, &mut erased_code, &[0, 1, 2, 3], &[0]);
+            assert!(matches!(res, Err(jerasure_rs::Error::TooManyErasure(_, _))));
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn decode_shards() -> Result<(), Box<dyn std::error::Error>> {
+    use jerasure_rs::erasure::{ErasureCodeBuilder, Technique};
+    let (k, m) = (5, 3);
+    let blk_size = 1 << 12;
+    for tech in [
+        Technique::Matrix,
+        Technique::BitMatrix,
+        Technique::Schedule,
+        Technique::ScheduleCache,
+    ] {
+        let ec = ErasureCodeBuilder::new()
+            .coding_method(CodingMethod::Cauchy)
+            .k(NonZeroI32::new(k).unwrap())
+            .m(NonZeroI32::new(m).unwrap())
+            .packet_size(NonZeroI32::new(64).unwrap())
+            .tech(tech)
+            .build()?;
+        let data = make_rand_blk(k as usize, blk_size);
+        let mut code = make_zero_blk(m as usize, blk_size);
+        ec.encode(&data, &mut code)?;
+        let stripe = data.iter().chain(&code).cloned().map(Some).collect_vec();
+        for erased in (0..(k + m) as usize)
+            .powerset()
+            .filter(|e| e.len() <= m as usize)
+        {
+            let mut shards = stripe.clone();
+            erased.iter().for_each(|&i| shards[i] = None);
+            ec.decode_shards(&mut shards)?;
+            assert_eq!(shards, stripe, "erased:{erased:?}");
+        }
+
+        // too many missing shards are left untouched
+        let mut shards = stripe.clone();
+        (0..=m as usize).for_each(|i| shards[i] = None);
+        let res = ec.decode_shards(&mut shards);
+        assert!(matches!(res, Err(jerasure_rs::Error::TooManyErasure(_, _))));
+        assert_eq!(
+            shards.iter().filter(|s| s.is_none()).count(),
+            m as usize + 1
+        );
+        // wrong number of shards
+        let res = ec.decode_shards(&mut stripe[1..].to_vec());
+        assert!(matches!(res, Err(jerasure_rs::Error::InvalidArguments(_))));
+        // shards of different lengths
+        let mut shards = stripe.clone();
+        shards[0] = None;
+        shards[1].as_mut().unwrap().truncate(blk_size / 2);
+        let res = ec.decode_shards(&mut shards);
+        assert!(matches!(res, Err(jerasure_rs::Error::InvalidArguments(_))));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn verify() -> Result<(), Box<dyn std::error::Error>> {
+    use jerasure_rs::erasure::{ErasureCodeBuilder, Technique};
+    let (k, m) = (6, 3);
+    let blk_size = 1 << 12;
+    for (method, tech) in [
+        (CodingMethod::ReedSolVand, Technique::Matrix),
+        (CodingMethod::Cauchy, Technique::BitMatrix),
+        (CodingMethod::Cauchy, Technique::Schedule),
+        (CodingMethod::Cauchy, Technique::ScheduleCache),
+    ] {
+        let ec = ErasureCodeBuilder::new()
+            .coding_method(method)
+            .k(NonZeroI32::new(k).unwrap())
+            .m(NonZeroI32::new(m).unwrap())
+            .packet_size(NonZeroI32::new(64).unwrap())
+            .tech(tech)
+            .build()?;
+        let data = make_rand_blk(k as usize, blk_size);
+        let mut code = make_zero_blk(m as usize, blk_size);
+        ec.encode(&data, &mut code)?;
+        assert!(ec.verify(&data, &code)?.is_empty());
+        assert_eq!(ec.locate_corruption(&data, &code)?, None);
+
+        for idx in 0..k + m {
+            let mut corrupted_data = data.clone();
+            let mut corrupted_code = code.clone();
+            let blk = match idx < k {
+                true => &mut corrupted_data[idx as usize],
+                false => &mut corrupted_code[(idx - k) as usize],
+            };
+            blk[100] ^= 0x5a;
+            blk[blk_size - 1] = blk[blk_size - 1].wrapping_add(1);
+            let mismatched = ec.verify(&corrupted_data, &corrupted_code)?;
+            match idx < k {
+                true => assert_eq!(mismatched, (k..k + m).collect_vec()),
+                false => assert_eq!(mismatched, vec![idx]),
+            }
+            let located = ec.locate_corruption(&corrupted_data, &corrupted_code)?;
+            assert_eq!(located, Some(idx));
+        }
+
+        // two corrupted devices
+        let mut corrupted_data = data.clone();
+        corrupted_data[0][0] ^= 1;
+        corrupted_data[3][0] ^= 1;
+        assert!(!ec.verify(&corrupted_data, &code)?.is_empty());
+        let res = ec.locate_corruption(&corrupted_data, &code);
+        assert!(matches!(res, Err(jerasure_rs::Error::Other(_))));
+        // wrong number of coding devices
+        let res = ec.verify(&data, &code[1..]);
+        assert!(matches!(res, Err(jerasure_rs::Error::InvalidArguments(_))));
+    }
+    // a single coding device cannot locate the corruption
+    let ec = ErasureCodeBuilder::new()
+        .coding_method(CodingMethod::ReedSolVand)
+        .k(NonZeroI32::new(k).unwrap())
+        .m(NonZeroI32::new(1).unwrap())
+        .tech(Technique::Matrix)
+        .build()?;
+    let data = make_rand_blk(k as usize, blk_size);
+    let mut code = make_zero_blk(1, blk_size);
+    ec.encode(&data, &mut code)?;
+    assert!(ec.verify(&data, &code)?.is_empty());
+    let res = ec.locate_corruption(&data, &code);
+    assert!(matches!(res, Err(jerasure_rs::Error::NotSupported(_))));
+
+    Ok(())
+}
+
+#[test]
+fn correct() -> Result<(), Box<dyn std::error::Error>> {
+    use jerasure_rs::erasure::{ErasureCodeBuilder, Technique};
+    let (k, m) = (5, 4);
+    let blk_size = 1 << 12;
+    for (method, tech) in [
+        (CodingMethod::ReedSolVand, Technique::Matrix),
+        (CodingMethod::Cauchy, Technique::Matrix),
+        (CodingMethod::Cauchy, Technique::BitMatrix),
+        (CodingMethod::CauchyOrig, Technique::Schedule),
+    ] {
+        let ec = ErasureCodeBuilder::new()
+            .coding_method(method)
+            .k(NonZeroI32::new(k).unwrap())
+            .m(NonZeroI32::new(m).unwrap())
+            .packet_size(NonZeroI32::new(64).unwrap())
+            .tech(tech)
+            .decoding_cache(std::num::NonZeroUsize::new(4).unwrap())
+            .build()?;
+        let data = make_rand_blk(k as usize, blk_size);
+        let mut code = make_zero_blk(m as usize, blk_size);
+        ec.encode(&data, &mut code)?;
+        let (mut d, mut c) = (data.clone(), code.clone());
+        assert!(ec.correct(&mut d, &mut c)?.is_empty());
+
+        for corrupted in (0..k + m).combinations(1).chain((0..k + m).combinations(2)) {
+            let (mut d, mut c) = (data.clone(), code.clone());
+            for &idx in &corrupted {
+                let blk = match idx < k {
+                    true => &mut d[idx as usize],
+                    false => &mut c[(idx - k) as usize],
+                };
+                blk[idx as usize * 7] ^= 0xa5;
+            }
+            let repaired = ec.correct(&mut d, &mut c)?;
+            assert_eq!(repaired, corrupted);
+            assert_eq!(d, data);
+            assert_eq!(c, code);
+        }
+        // the hypotheses are not cached
+        assert_eq!(ec.decoding_cache_stats().unwrap().len, 0);
+    }
+
+    // a single coding device cannot correct a corruption
+    let ec = ErasureCodeBuilder::new()
+        .coding_method(CodingMethod::ReedSolVand)
+        .k(NonZeroI32::new(k).unwrap())
+        .m(NonZeroI32::new(1).unwrap())
+        .tech(Technique::Matrix)
+        .build()?;
+    let mut data = make_rand_blk(k as usize, blk_size);
+    let mut code = make_zero_blk(1, blk_size);
+    let res = ec.correct(&mut data, &mut code);
+    assert!(matches!(res, Err(jerasure_rs::Error::NotSupported(_))));
+    // nor a code defined by a bit-matrix only
+    let ec = ErasureCodeBuilder::new()
+        .coding_method(CodingMethod::Liberation)
+        .k(NonZeroI32::new(k).unwrap())
+        .m(NonZeroI32::new(2).unwrap())
+        .w(jerasure_rs::CodeWord::Other(7))
+        .packet_size(NonZeroI32::new(64).unwrap())
+        .tech(Technique::BitMatrix)
+        .build()?;
+    let mut data = make_rand_blk(k as usize, 7 * 64);
+    let mut code = make_zero_blk(2, 7 * 64);
+    let res = ec.correct(&mut data, &mut code);
+    assert!(matches!(res, Err(jerasure_rs::Error::NotSupported(_))));
+
+    Ok(())
+}
+
+#[test]
+fn encode_parity() -> Result<(), Box<dyn std::error::Error>> {
+    use jerasure_rs::erasure::{ErasureCodeBuilder, Technique};
+    let k = 6;
+    let blk_size = 1 << 12;
+    for (method, tech, m) in [
+        (CodingMethod::ReedSolVand, Technique::Matrix, 3),
+        (CodingMethod::ReedSolR6, Technique::Matrix, 2),
+        (CodingMethod::Cauchy, Technique::Matrix, 3),
+        (CodingMethod::Cauchy, Technique::BitMatrix, 3),
+        (CodingMethod::CauchyOrig, Technique::Schedule, 4),
+        (CodingMethod::Cauchy, Technique::ScheduleCache, 2),
+    ] {
+        let ec = ErasureCodeBuilder::new()
+            .coding_method(method)
+            .k(NonZeroI32::new(k).unwrap())
+            .m(NonZeroI32::new(m).unwrap())
+            .packet_size(NonZeroI32::new(64).unwrap())
+            .tech(tech)
+            .build()?;
+        let data = make_rand_blk(k as usize, blk_size);
+        let mut code = make_zero_blk(m as usize, blk_size);
+        ec.encode(&data, &mut code)?;
+        for idx in k..k + m {
+            let mut out = vec![0xff_u8; blk_size];
+            ec.encode_parity(idx, &data, &mut out)?;
+            assert_eq!(out, code[(idx - k) as usize]);
+        }
+
+        // invalid arguments
+        let mut out = vec![0_u8; blk_size];
+        for idx in [-1, 0, k - 1, k + m] {
+            let res = ec.encode_parity(idx, &data, &mut out);
+            assert!(matches!(res, Err(jerasure_rs::Error::InvalidArguments(_))));
+        }
+        let res = ec.encode_parity(k, &data[1..], &mut out);
+        assert!(matches!(res, Err(jerasure_rs::Error::InvalidArguments(_))));
+        let res = ec.encode_parity(k, &data, &mut out[8..]);
+        assert!(matches!(res, Err(jerasure_rs::Error::InvalidArguments(_))));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn update_parity() -> Result<(), Box<dyn std::error::Error>> {
+    use jerasure_rs::erasure::{ErasureCodeBuilder, Technique};
+    let k = 6;
+    let blk_size = 1 << 12;
+    for (method, tech, m, w) in [
+        (CodingMethod::ReedSolVand, Technique::Matrix, 3, 8),
+        (CodingMethod::ReedSolVand, Technique::Matrix, 3, 16),
+        (CodingMethod::ReedSolR6, Technique::Matrix, 2, 32),
+        (CodingMethod::Cauchy, Technique::BitMatrix, 3, 8),
+        (CodingMethod::CauchyOrig, Technique::Schedule, 4, 4),
+        (CodingMethod::Cauchy, Technique::ScheduleCache, 2, 8),
+        (CodingMethod::Liberation, Technique::BitMatrix, 2, 7),
+    ] {
+        let ec = ErasureCodeBuilder::new()
+            .coding_method(method)
+            .k(NonZeroI32::new(k).unwrap())
+            .m(NonZeroI32::new(m).unwrap())
+            .w(jerasure_rs::CodeWord::from_u8(w))
+            .packet_size(NonZeroI32::new(64).unwrap())
+            .tech(tech)
+            .build()?;
+        let blk_size = blk_size - blk_size % (64 * w as usize);
+        let mut data = make_rand_blk(k as usize, blk_size);
+        let mut code = make_zero_blk(m as usize, blk_size);
+        ec.encode(&data, &mut code)?;
+
+        for idx in 0..k {
+            let new = make_rand_blk(1, blk_size).pop().unwrap();
+            ec.update_parity(idx, &data[idx as usize], &new, &mut code)?;
+            data[idx as usize] = new;
+            let mut expect = make_zero_blk(m as usize, blk_size);
+            ec.encode(&data, &mut expect)?;
+            assert_eq!(code, expect);
+        }
+
+        // delta of a partial write
+        let mut delta = vec![0_u8; blk_size];
+        delta[8..72].copy_from_slice(&make_rand_blk(1, 64)[0]);
+        ec.update_parity_with_delta(k - 1, &delta, &mut code)?;
+        data[k as usize - 1]
+            .iter_mut()
+            .zip(&delta)
+            .for_each(|(d, x)| *d ^= x);
+        let mut expect = make_zero_blk(m as usize, blk_size);
+        ec.encode(&data, &mut expect)?;
+        assert_eq!(code, expect);
+
+        // invalid arguments
+        let res = ec.update_parity_with_delta(k, &delta, &mut code);
+        assert!(matches!(res, Err(jerasure_rs::Error::InvalidArguments(_))));
+        let res = ec.update_parity_with_delta(0, &delta, &mut code[1..]);
+        assert!(matches!(res, Err(jerasure_rs::Error::InvalidArguments(_))));
+        let res = ec.update_parity(0, &delta, &delta[8..], &mut code);
+        assert!(matches!(res, Err(jerasure_rs::Error::InvalidArguments(_))));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn galois_field() -> Result<(), Box<dyn std::error::Error>> {
+    use jerasure_rs::erasure::{ErasureCodeBuilder, Technique};
+    use jerasure_rs::galois::{GaloisFieldBuilder, RegionType};
+    let (k, m) = (5, 3);
+    let builder = |method: CodingMethod| {
+        ErasureCodeBuilder::new()
+            .coding_method(method)
+            .k(NonZeroI32::new(k).unwrap())
+            .m(NonZeroI32::new(m).unwrap())
+            .packet_size(NonZeroI32::new(64).unwrap())
+    };
+
+    // the matrices generated in a field with the default polynomial match the ones of Jerasure
+    for (w, poly) in [(8, 0x11d), (16, 0x1100b), (32, 0x400007)] {
+        let w = jerasure_rs::CodeWord::from_u8(w);
+        let gf = GaloisFieldBuilder::new().w(w).prim_poly(poly).build()?;
+        for (method, m) in [
+            (CodingMethod::ReedSolVand, m),
+            (CodingMethod::ReedSolR6, 2),
+            (CodingMethod::Cauchy, m),
+            (CodingMethod::CauchyOrig, m),
+        ] {
+            let m = NonZeroI32::new(m).unwrap();
+            let expect = builder(method).m(m).w(w).tech(Technique::Matrix).build()?;
+            let ec = builder(method)
+                .m(m)
+                .galois_field(gf.clone())
+                .tech(Technique::Matrix)
+                .build()?;
+            assert!(ec.galois_field().is_some());
+            assert_eq!(ec.coding_matrix(), expect.coding_matrix(), "{method:?}");
+            assert_eq!(ec.n_ones(), expect.n_ones(), "{method:?}");
+        }
+    }
+    let gf =
+        jerasure_rs::galois::GaloisField::try_from_code_word(jerasure_rs::CodeWord::W8).unwrap();
+    let ec = builder(CodingMethod::ReedSolVand)
+        .galois_field(gf)
+        .tech(Technique::Matrix)
+        .build()?;
+    assert!(ec.galois_field().is_none());
+
+    // the Cauchy matrix of ISA-L in another field
+    let gf = GaloisFieldBuilder::new().prim_poly(0x12d).build()?;
+    let isal = (k..k + m)
+        .cartesian_product(0..k)
+        .map(|(i, j)| gf.inverse(i ^ j))
+        .collect_vec();
+    let blk_size = 64 * 8 * 8;
+    let data = make_rand_blk(k as usize, blk_size);
+    for tech in [
+        Technique::Matrix,
+        Technique::BitMatrix,
+        Technique::Schedule,
+        Technique::ScheduleCache,
+    ] {
+        let ec = builder(CodingMethod::CauchyXY)
+            .cauchy_xy((k..k + m).collect_vec(), (0..k).collect_vec())
+            .galois_field(gf.clone())
+            .tech(tech)
+            .build()?;
+        assert_eq!(ec.coding_matrix(), Some(isal.clone()));
+        if let Some(bmat) = ec.coding_bitmatrix() {
+            // the column `c` of the block of `e` is `e * 2^c`
+            let (w, cols) = (8, k as usize * 8);
+            for (idx, &e) in isal.iter().enumerate() {
+                let (i, j) = (idx / k as usize, idx % k as usize);
+                for (r, c) in (0..w).cartesian_product(0..w) {
+                    let bit = (gf.multiply(e, 1 << c) >> r) & 1;
+                    assert_eq!(bmat[(i * w + r) * cols + j * w + c], bit, "{tech:?}");
+                }
+            }
+        } else {
+            let mut code = make_zero_blk(m as usize, blk_size);
+            ec.encode(&data, &mut code)?;
+            for (row, parity) in isal.chunks(k as usize).zip(&code) {
+                let mut expect = vec![0_u8; blk_size];
+                for (&e, d) in row.iter().zip(&data) {
+                    gf.region_multiply(d, e, 1, &mut expect)?;
+                }
+                assert_eq!(*parity, expect, "{tech:?}");
+            }
+        }
+        double_erasure_test(ec)?;
+    }
+    for method in [
+        CodingMethod::ReedSolVand,
+        CodingMethod::ReedSolR6,
+        CodingMethod::Cauchy,
+        CodingMethod::CauchyOrig,
+    ] {
+        let m = if matches!(method, CodingMethod::ReedSolR6) {
+            2
+        } else {
+            m
+        };
+        let ec = builder(method)
+            .m(NonZeroI32::new(m).unwrap())
+            .galois_field(gf.clone())
+            .tech(Technique::Matrix)
+            .build()?;
+        let mut code = make_zero_blk(m as usize, blk_size);
+        ec.encode(&data, &mut code)?;
+        let mut expect = make_zero_blk(1, blk_size).pop().unwrap();
+        ec.encode_parity(k + m - 1, &data, &mut expect)?;
+        assert_eq!(code[m as usize - 1], expect);
+        double_erasure_test(ec)?;
+    }
+    let ec = builder(CodingMethod::Custom)
+        .coding_matrix(isal.clone())
+        .galois_field(gf.clone())
+        .tech(Technique::Matrix)
+        .build()?;
+    double_erasure_test(ec)?;
+
+    // a code in a field whose multiplication writes to its scratch memory is shared between threads
+    let group = GaloisFieldBuilder::new()
+        .w(jerasure_rs::CodeWord::W16)
+        .mult_type(jerasure_rs::galois::MultType::Group)
+        .args(4, 4)
+        .build()?;
+    let ec = builder(CodingMethod::Cauchy)
+        .galois_field(group)
+        .tech(Technique::Matrix)
+        .build()?;
+    let mut expect = make_zero_blk(m as usize, blk_size);
+    ec.encode(&data, &mut expect)?;
+    std::thread::scope(|s| {
+        for _ in 0..4 {
+            s.spawn(|| {
+                for _ in 0..10 {
+                    let mut code = make_zero_blk(m as usize, blk_size);
+                    ec.encode(&data, &mut code).unwrap();
+                    assert_eq!(code, expect);
+                }
+            });
+        }
+    });
+
+    // unsupported fields
+    let res = builder(CodingMethod::Liberation)
+        .m(NonZeroI32::new(2).unwrap())
+        .galois_field(
+            GaloisFieldBuilder::new()
+                .w(jerasure_rs::CodeWord::Other(7))
+                .build()?,
+        )
+        .tech(Technique::BitMatrix)
+        .build();
+    assert!(matches!(res, Err(jerasure_rs::Error::NotSupported(_))));
+    let altmap = GaloisFieldBuilder::new()
+        .w(jerasure_rs::CodeWord::W16)
+        .mult_type(jerasure_rs::galois::MultType::SplitTable)
+        .region_type(RegionType::ALTMAP)
+        .args(16, 4)
+        .build()?;
+    let res = builder(CodingMethod::Cauchy)
+        .galois_field(altmap)
+        .tech(Technique::Matrix)
+        .build();
+    assert!(matches!(res, Err(jerasure_rs::Error::NotSupported(_))));
+    let res = builder(CodingMethod::Cauchy)
+        .galois_field(gf.clone())
+        .w(jerasure_rs::CodeWord::W16)
+        .tech(Technique::Matrix)
+        .build();
+    assert!(matches!(res, Err(jerasure_rs::Error::InvalidArguments(_))));
+    // the coding matrix is not MDS in the field of 0x12d
+    let mut singular = isal.clone();
+    singular[1] = gf.multiply(
+        singular[0],
+        gf.divide(singular[k as usize + 1], singular[k as usize]),
+    );
+    let res = builder(CodingMethod::Custom)
+        .coding_matrix(singular)
+        .galois_field(gf)
+        .tech(Technique::Matrix)
+        .build();
+    assert!(matches!(res, Err(jerasure_rs::Error::InvalidArguments(_))));
+
+    Ok(())
+}
+
+#[test]
+fn composite_field() -> Result<(), Box<dyn std::error::Error>> {
+    use jerasure_rs::CodeWord;
+    use jerasure_rs::erasure::{ErasureCodeBuilder, Technique};
+    use jerasure_rs::galois::{GaloisFieldBuilder, MultType};
+    let (k, m) = (5, 3);
+    let blk_size = 1 << 12;
+    // GF((2^16)^2)
+    let base = GaloisFieldBuilder::new().w(CodeWord::W16).build()?;
+    let gf = GaloisFieldBuilder::new()
+        .w(CodeWord::W32)
+        .mult_type(MultType::Composite)
+        .args(2, 0)
+        .base_field(base)
+        .build()?;
+    let data = make_rand_blk(k as usize, blk_size);
+    for method in [CodingMethod::ReedSolVand, CodingMethod::Cauchy] {
+        let ec = ErasureCodeBuilder::new()
+            .coding_method(method)
+            .k(NonZeroI32::new(k).unwrap())
+            .m(NonZeroI32::new(m).unwrap())
+            .galois_field(gf.clone())
+            .tech(Technique::Matrix)
+            .build()?;
+        assert!(matches!(ec.w(), CodeWord::W32));
+        assert_eq!(ec.galois_field(), Some(&gf));
+        let matrix = ec.coding_matrix().unwrap();
+        let mut code = make_zero_blk(m as usize, blk_size);
+        ec.encode(&data, &mut code)?;
+        for (row, parity) in matrix.chunks(k as usize).zip(&code) {
+            let mut expect = vec![0_u8; blk_size];
+            for (&e, d) in row.iter().zip(&data) {
+                gf.region_multiply(d, e, 1, &mut expect)?;
+            }
+            assert_eq!(*parity, expect, "{method:?}");
+        }
+        double_erasure_test(ec)?;
+    }
+
+    Ok(())
+}
+
+#[test]
+fn repair_plan() -> Result<(), Box<dyn std::error::Error>> {
+    use jerasure_rs::erasure::{ErasureCodeBuilder, Technique};
+    let (k, m) = (4, 3);
+    let blk_size = 1 << 12;
+    for tech in [Technique::Matrix, Technique::BitMatrix, Technique::Schedule] {
+        let ec = ErasureCodeBuilder::new()
+            .coding_method(CodingMethod::Cauchy)
+            .k(NonZeroI32::new(k).unwrap())
+            .m(NonZeroI32::new(m).unwrap())
+            .packet_size(NonZeroI32::new(64).unwrap())
+            .tech(tech)
+            .build()?;
+        let data = make_rand_blk(k as usize, blk_size);
+        let mut code = make_zero_blk(m as usize, blk_size);
+        ec.encode(&data, &mut code)?;
+        let device = |idx: i32| match idx < k {
+            true => &data[idx as usize],
+            false => &code[(idx - k) as usize],
+        };
+        for available in (0..k + m).powerset().filter(|a| a.len() >= k as usize) {
+            let lost = (0..k + m).filter(|i| !available.contains(i)).collect_vec();
+            for targets in lost.iter().copied().powerset().filter(|t| !t.is_empty()) {
+                let plan = ec.repair_plan(&targets, &available)?;
+                assert_eq!(plan.targets(), targets.as_slice());
+                assert!(plan.sources().len() <= k as usize);
+                assert!(plan.sources().iter().all(|s| available.contains(s)));
+                let sources = plan.sources().iter().map(|&s| device(s)).collect_vec();
+                let mut repaired = make_zero_blk(targets.len(), blk_size);
+                ec.repair(&plan, &sources, &mut repaired)?;
+                for (t, buf) in targets.iter().zip(&repaired) {
+                    assert_eq!(
+                        buf,
+                        device(*t),
+                        "available:{available:?} targets:{targets:?}"
+                    );
+                }
+            }
+        }
+        // repairing a data device reads k devices
+        let plan = ec.repair_plan(&[1], &[0, 2, 3, 4])?;
+        assert_eq!(plan.sources(), &[0, 2, 3, 4]);
+        // a coding device only needs the data devices
+        let plan = ec.repair_plan(&[5], &[0, 1, 2, 3, 4, 6])?;
+        assert_eq!(plan.sources(), &[0, 1, 2, 3]);
+
+        // a target available
+        let res = ec.repair_plan(&[0], &[0, 1, 2, 3]);
+        assert!(matches!(res, Err(jerasure_rs::Error::InvalidArguments(_))));
+        // index out of bounds
+        let res = ec.repair_plan(&[k + m], &[0, 1, 2, 3]);
+        assert!(matches!(res, Err(jerasure_rs::Error::InvalidArguments(_))));
+        // too few available
+        let res = ec.repair_plan(&[0], &[1, 2, 3]);
+        assert!(matches!(res, Err(jerasure_rs::Error::TooManyErasure(_, _))));
+        // wrong number of buffers
+        let plan = ec.repair_plan(&[0], &[1, 2, 3, 4])?;
+        let mut repaired = make_zero_blk(2, blk_size);
+        let res = ec.repair(&plan, &data[1..], &mut repaired);
+        assert!(matches!(res, Err(jerasure_rs::Error::InvalidArguments(_))));
+    }
+    // a plan of another code
+    let ec = ErasureCodeBuilder::new()
+        .coding_method(CodingMethod::Cauchy)
+        .k(NonZeroI32::new(k).unwrap())
+        .m(NonZeroI32::new(m).unwrap())
+        .tech(Technique::Matrix)
+        .build()?;
+    let other = ErasureCodeBuilder::new()
+        .coding_method(CodingMethod::Cauchy)
+        .k(NonZeroI32::new(k + 1).unwrap())
+        .m(NonZeroI32::new(m).unwrap())
+        .tech(Technique::Matrix)
+        .build()?;
+    let plan = other.repair_plan(&[0], &[1, 2, 3, 4, 5])?;
+    let sources = make_rand_blk(plan.sources().len(), blk_size);
+    let mut repaired = make_zero_blk(1, blk_size);
+    let res = ec.repair(&plan, &sources, &mut repaired);
+    assert!(matches!(res, Err(jerasure_rs::Error::InvalidArguments(_))));
+    // a plan of a code with the same shape but another coding matrix
+    let other = ErasureCodeBuilder::new()
+        .coding_method(CodingMethod::ReedSolVand)
+        .k(NonZeroI32::new(k).unwrap())
+        .m(NonZeroI32::new(m).unwrap())
+        .tech(Technique::Matrix)
+        .build()?;
+    let plan = other.repair_plan(&[0], &[1, 2, 3, 4])?;
+    let sources = make_rand_blk(plan.sources().len(), blk_size);
+    let res = ec.repair(&plan, &sources, &mut repaired);
+    assert!(matches!(res, Err(jerasure_rs::Error::InvalidArguments(_))));
+
+    Ok(())
+}
+
+#[test]
+fn liberation() -> Result<(), Box<dyn std::error::Error>> {
+    use jerasure_rs::erasure::Technique;
+    let method = CodingMethod::Liberation;
+    for (k, w) in [(2, 3), (4, 5), (7, 7), (6, 11)] {
+        for tech in [
+            Technique::BitMatrix,
+            Technique::Schedule,
+            Technique::ScheduleCache,
+        ] {
+            let ec = jerasure_rs::erasure::ErasureCodeBuilder::new()
+                .coding_method(method)
+                .k(NonZeroI32::new(k).unwrap())
+                .m(NonZeroI32::new(2).unwrap())
+                .w(jerasure_rs::CodeWord::Other(w))
+                .packet_size(NonZeroI32::new(128).unwrap())
+                .tech(tech)
+                .build()?;
+            let blk_size = aligned_blk_size(&ec);
+            general_test_with(ec, blk_size)?;
+        }
+    }
+
+    // # invalid parameters
+    for (k, m, w, tech) in [
+        // matrix technique
+        (4, 2, 5, Technique::Matrix),
+        // m != 2
+        (4, 3, 5, Technique::BitMatrix),
+        // w not prime
+        (4, 2, 8, Technique::BitMatrix),
+        // w not greater than 2
+        (2, 2, 2, Technique::Schedule),
+        // k > w
+        (6, 2, 5, Technique::ScheduleCache),
+    ] {
+        let ec = jerasure_rs::erasure::ErasureCodeBuilder::new()
+            .coding_method(method)
+            .k(NonZeroI32::new(k).unwrap())
+            .m(NonZeroI32::new(m).unwrap())
+            .w(jerasure_rs::CodeWord::from_u8(w))
+            .packet_size(NonZeroI32::new(128).unwrap())
+            .tech(tech)
+            .build();
+        assert!(
+            matches!(ec, Err(jerasure_rs::Error::NotSupported(_))),
+            "k:{k} m:{m} w:{w} tech:{tech:?}"
+        );
+    }
+
+    // # block size not multiple of packet_size * w
+    let ec = jerasure_rs::erasure::ErasureCodeBuilder::new()
+        .coding_method(method)
+        .k(NonZeroI32::new(4).unwrap())
+        .m(NonZeroI32::new(2).unwrap())
+        .w(jerasure_rs::CodeWord::Other(5))
+        .packet_size(NonZeroI32::new(128).unwrap())
+        .tech(Technique::BitMatrix)
+        .build()?;
+    let data = make_rand_blk(4, 128 * 4);
+    let mut code = make_zero_blk(2, 128 * 4);
+    let res = ec.encode(&data, &mut code);
+    assert!(matches!(res, Err(jerasure_rs::Error::InvalidArguments(_))));
+
+    Ok(())
+}
+
+#[test]
+fn unaligned_packets() -> Result<(), Box<dyn std::error::Error>> {
+    use jerasure_rs::erasure::{ErasureCodeBuilder, Technique};
+    let (k, m) = (4, 2);
+    // BLK_SIZE of general_test is not a multiple of packet_size * w of these codes
+    for (method, w, packet_size) in [
+        (CodingMethod::Cauchy, 8, 96),
+        (CodingMethod::Liberation, 7, 128),
+    ] {
+        for tech in [
+            Technique::BitMatrix,
+            Technique::Schedule,
+            Technique::ScheduleCache,
+        ] {
+            let ec = ErasureCodeBuilder::new()
+                .coding_method(method)
+                .k(NonZeroI32::new(k).unwrap())
+                .m(NonZeroI32::new(m).unwrap())
+                .w(jerasure_rs::CodeWord::from_u8(w))
+                .packet_size(NonZeroI32::new(packet_size).unwrap())
+                .tech(tech)
+                .build()?;
+            let mut data = make_rand_blk(k as usize, BLK_SIZE);
+            let mut code = make_zero_blk(m as usize, BLK_SIZE);
+            let res = ec.encode(&data, &mut code);
+            assert!(
+                matches!(res, Err(jerasure_rs::Error::InvalidArguments(_))),
+                "{method:?} {tech:?}"
+            );
+            let res = ec.decode(&mut data, &mut code, &[0]);
+            assert!(
+                matches!(res, Err(jerasure_rs::Error::InvalidArguments(_))),
+                "{method:?} {tech:?}"
+            );
+            let new = make_rand_blk(1, BLK_SIZE).remove(0);
+            let res = ec.update_parity(0, &data[0], &new, &mut code);
+            assert!(
+                matches!(res, Err(jerasure_rs::Error::InvalidArguments(_))),
+                "{method:?} {tech:?}"
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn liber8tion() -> Result<(), Box<dyn std::error::Error>> {
+    use jerasure_rs::erasure::Technique;
+    let method = CodingMethod::Liber8tion;
+    for k in 1..=8 {
+        for tech in [
+            Technique::BitMatrix,
+            Technique::Schedule,
+            Technique::ScheduleCache,
+        ] {
+            let ec = jerasure_rs::erasure::ErasureCodeBuilder::new()
+                .coding_method(method)
+                .k(NonZeroI32::new(k).unwrap())
+                .m(NonZeroI32::new(2).unwrap())
+                .w(jerasure_rs::CodeWord::W8)
+                .packet_size(NonZeroI32::new(64).unwrap())
+                .tech(tech)
+                .build()?;
+            double_erasure_test(ec)?;
+        }
+    }
+
+    // # invalid parameters
+    for (k, m, w, tech) in [
+        // matrix technique
+        (4, 2, 8, Technique::Matrix),
+        // m != 2
+        (4, 3, 8, Technique::BitMatrix),
+        // w != 8
+        (4, 2, 16, Technique::Schedule),
+        (4, 2, 7, Technique::Schedule),
+        // k > 8
+        (9, 2, 8, Technique::ScheduleCache),
+    ] {
+        let ec = jerasure_rs::erasure::ErasureCodeBuilder::new()
+            .coding_method(method)
+            .k(NonZeroI32::new(k).unwrap())
+            .m(NonZeroI32::new(m).unwrap())
+            .w(jerasure_rs::CodeWord::from_u8(w))
+            .packet_size(NonZeroI32::new(64).unwrap())
+            .tech(tech)
+            .build();
+        assert!(
+            matches!(ec, Err(jerasure_rs::Error::NotSupported(_))),
+            "k:{k} m:{m} w:{w} tech:{tech:?}"
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn blaum_roth() -> Result<(), Box<dyn std::error::Error>> {
+    use jerasure_rs::erasure::Technique;
+    let method = CodingMethod::BlaumRoth;
+    for (k, w) in [(3, 4), (6, 6), (7, 10), (8, 16)] {
+        for tech in [
+            Technique::BitMatrix,
+            Technique::Schedule,
+            Technique::ScheduleCache,
+        ] {
+            let ec = jerasure_rs::erasure::ErasureCodeBuilder::new()
+                .coding_method(method)
+                .k(NonZeroI32::new(k).unwrap())
+                .m(NonZeroI32::new(2).unwrap())
+                .w(jerasure_rs::CodeWord::from_u8(w))
+                .packet_size(NonZeroI32::new(64).unwrap())
+                .tech(tech)
+                .build()?;
+            let blk_size = aligned_blk_size(&ec);
+            general_test_with(ec, blk_size)?;
+        }
+    }
+
+    // # invalid parameters
+    for (k, m, w, tech) in [
+        // matrix technique
+        (4, 2, 4, Technique::Matrix),
+        // m != 2
+        (4, 3, 4, Technique::BitMatrix),
+        // w + 1 not prime
+        (4, 2, 8, Technique::BitMatrix),
+        // w not greater than 2
+        (2, 2, 2, Technique::Schedule),
+        // k > w
+        (7, 2, 6, Technique::ScheduleCache),
+    ] {
+        let ec = jerasure_rs::erasure::ErasureCodeBuilder::new()
+            .coding_method(method)
+            .k(NonZeroI32::new(k).unwrap())
+            .m(NonZeroI32::new(m).unwrap())
+            .w(jerasure_rs::CodeWord::from_u8(w))
+            .packet_size(NonZeroI32::new(64).unwrap())
+            .tech(tech)
+            .build();
+        assert!(
+            matches!(ec, Err(jerasure_rs::Error::NotSupported(_))),
+            "k:{k} m:{m} w:{w} tech:{tech:?}"
+        );
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, PartialEq)]
+enum Expect {
+    Ok,
+    InvalidArguments,
+    NotSupported,
+}
+
+/// The parameters of a case of `builder_compatibility` besides the grid.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Extra {
+    Unset,
+    XyMissing,
+    XyValid,
+    XyDuplicate,
+    CustomMissing,
+    CustomMatrix,
+    CustomBitMatrix,
+}
+
+/// The Galois field set on the builder of a case of `builder_compatibility`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FieldSetup {
+    Unset,
+    /// a field of w built by `GaloisFieldBuilder`
+    Same,
+    /// a field of another w, which is overridden by `ErasureCodeBuilder::w`
+    OtherW,
+    /// a field of w = 16 storing the regions in the ALTMAP layout
+    AltMap,
+}
+
+/// The expected result of `ErasureCodeBuilder::build` from its documentation, where the
+/// general parameters are checked first, then the field, the combination of the table,
+/// and finally the parameters of the technique and of the method.
+fn expect_build(
+    method: CodingMethod,
+    extra: Extra,
+    field: FieldSetup,
+    tech: jerasure_rs::erasure::Technique,
+    (w, k, m): (i32, i32, i32),
+    packet_size: Option<i32>,
+) -> Expect {
+    use CodingMethod::*;
+    use jerasure_rs::erasure::Technique::{self, *};
+    const BITS: &[Technique] = &[BitMatrix, Schedule, ScheduleCache];
+    fn is_prime(n: i32) -> bool {
+        n >= 2 && (2..n).all(|i| n % i != 0)
+    }
+    // neither `CodingMethod` nor `Technique` implements `PartialEq`
+    fn has<T>(set: &[T], x: T) -> bool {
+        set.iter()
+            .any(|y| std::mem::discriminant(y) == std::mem::discriminant(&x))
+    }
+    // the rows of the compatibility table: (methods, techniques, constraints on w, k and m)
+    type Row = (
+        &'static [CodingMethod],
+        &'static [Technique],
+        fn(i32, i32, i32) -> bool,
+    );
+    let table: [Row; 6] = [
+        (
+            &[ReedSolVand, Cauchy, CauchyOrig, CauchyXY],
+            &[Matrix],
+            |w, _, _| matches!(w, 8 | 16 | 32),
+        ),
+        (&[ReedSolR6], &[Matrix], |w, _, m| {
+            matches!(w, 8 | 16 | 32) && m == 2
+        }),
+        (&[Cauchy, CauchyOrig, CauchyXY], BITS, |_, _, _| true),
+        (&[Liberation], BITS, |w, k, m| {
+            m == 2 && w > 2 && is_prime(w) && k <= w
+        }),
+        (&[Liber8tion], BITS, |w, k, m| m == 2 && w == 8 && k <= 8),
+        (&[BlaumRoth], BITS, |w, k, m| {
+            m == 2 && w > 2 && is_prime(w + 1) && k <= w
+        }),
+    ];
+
+    if k <= 0 || m <= 0 {
+        return Expect::InvalidArguments;
+    }
+    if !(1..=32).contains(&w) {
+        return Expect::NotSupported;
+    }
+    if i64::from(k) + i64::from(m) > 1_i64 << w {
+        return Expect::InvalidArguments;
+    }
+    match field {
+        FieldSetup::OtherW => return Expect::InvalidArguments,
+        FieldSetup::AltMap => return Expect::NotSupported,
+        FieldSetup::Same
+            if matches!(method, Liberation | Liber8tion | BlaumRoth)
+                || extra == Extra::CustomBitMatrix =>
+        {
+            return Expect::NotSupported;
+        }
+        _ => {}
+    }
+    let supported = match extra {
+        Extra::CustomMissing => return Expect::InvalidArguments,
+        // Custom follows Cauchy with a coding matrix, and all but Matrix with a bit-matrix
+        Extra::CustomMatrix => table
+            .iter()
+            .any(|(methods, techs, ok)| has(methods, Cauchy) && has(techs, tech) && ok(w, k, m)),
+        Extra::CustomBitMatrix => has(BITS, tech),
+        _ => table
+            .iter()
+            .any(|(methods, techs, ok)| has(methods, method) && has(techs, tech) && ok(w, k, m)),
+    };
+    if !supported {
+        return Expect::NotSupported;
+    }
+    if !matches!(tech, Matrix)
+        && !packet_size
+            .is_some_and(|p| p > 0 && (p as usize).is_multiple_of(size_of::<std::ffi::c_long>()))
+    {
+        return Expect::InvalidArguments;
+    }
+    if matches!(extra, Extra::XyMissing | Extra::XyDuplicate) {
+        return Expect::InvalidArguments;
+    }
+    Expect::Ok
+}
+
+/// Builds the case of `builder_compatibility`, and checks that a built code recovers
+/// from the erasure of m random devices.
+fn build_case(
+    method: CodingMethod,
+    extra: Extra,
+    field: Option<&jerasure_rs::galois::GaloisField>,
+    field_setup: FieldSetup,
+    tech: jerasure_rs::erasure::Technique,
+    (w, k, m): (i32, i32, i32),
+    packet_size: Option<i32>,
+) -> Result<Expect, Box<dyn std::error::Error>> {
+    use jerasure_rs::CodeWord;
+    let code_word = CodeWord::from_u8(w as u8);
+    let mut builder = jerasure_rs::erasure::ErasureCodeBuilder::new()
+        .coding_method(method)
+        .k(NonZeroI32::new(k).unwrap())
+        .m(NonZeroI32::new(m).unwrap())
+        .w(code_word)
+        .tech(tech);
+    if let Some(gf) = field {
+        builder = builder.galois_field(gf.clone());
+        if field_setup == FieldSetup::OtherW {
+            builder = builder.w(code_word);
+        }
+    }
+    if let Some(packet_size) = packet_size {
+        builder = builder.packet_size(NonZeroI32::new(packet_size).unwrap());
+    }
+    // the Cauchy matrix of ISA-L in the field of the code, if its elements fit in w
+    let cauchy = || {
+        let default = jerasure_rs::galois::GaloisField::try_from_code_word(code_word);
+        match field
+            .filter(|_| field_setup == FieldSetup::Same)
+            .or(default.as_ref())
+        {
+            Some(gf)
+                if (1..=32).contains(&w) && k > 0 && m > 0 && i64::from(k + m) <= 1_i64 << w =>
+            {
+                let mat = (k..k + m)
+                    .cartesian_product(0..k)
+                    .map(|(i, j)| gf.inverse(i ^ j))
+                    .collect_vec();
+                Some(jerasure_rs::matrix::Matrix::new(
+                    gf.clone(),
+                    m as usize,
+                    k as usize,
+                    mat,
+                ))
+            }
+            _ => None,
+        }
+    };
+    builder = match extra {
+        Extra::XyValid => builder.cauchy_xy(Vec::from_iter(0..m), Vec::from_iter(m..m + k)),
+        // the element 0 is in both X and Y
+        Extra::XyDuplicate => builder.cauchy_xy(Vec::from_iter(0..m), Vec::from_iter(0..k)),
+        Extra::CustomMatrix => {
+            let mat = cauchy().transpose()?;
+            builder.coding_matrix(mat.map_or(vec![], |mat| mat.as_slice().to_vec()))
+        }
+        Extra::CustomBitMatrix => {
+            let bmat = cauchy()
+                .transpose()?
+                .map(|mat| jerasure_rs::matrix::BitMatrix::from(&mat));
+            builder.coding_bitmatrix(bmat.map_or(vec![], |bmat| bmat.as_slice().to_vec()))
+        }
+        _ => builder,
+    };
+    let ec = match builder.build() {
+        Ok(ec) => ec,
+        Err(jerasure_rs::Error::InvalidArguments(_)) => return Ok(Expect::InvalidArguments),
+        Err(jerasure_rs::Error::NotSupported(_)) => return Ok(Expect::NotSupported),
+        Err(e) => return Err(e.into()),
+    };
+    let blk_size = ec
+        .packet_size()
+        .map_or(64, |packet_size| packet_size as usize * w as usize);
+    let data = make_rand_blk(k as usize, blk_size);
+    let mut code = make_zero_blk(m as usize, blk_size);
+    ec.encode(&data, &mut code)?;
+    let (mut erased_data, mut erased_code) = (data.clone(), code.clone());
+    let mut erased = Vec::from_iter(0..k + m);
+    erased.shuffle(&mut rand::rng());
+    erased.truncate(m as usize);
+    for &i in &erased {
+        match i < k {
+            true => erased_data[i as usize].fill(0),
+            false => erased_code[(i - k) as usize].fill(0),
+        }
+    }
+    ec.decode(&mut erased_data, &mut erased_code, &erased)?;
+    assert_eq!(erased_data, data, "erased:{erased:?}");
+    assert_eq!(erased_code, code, "erased:{erased:?}");
+    Ok(Expect::Ok)
+}
+
+#[test]
+fn builder_compatibility() -> Result<(), Box<dyn std::error::Error>> {
+    use CodingMethod::*;
+    use Technique::*;
+    use jerasure_rs::CodeWord;
+    use jerasure_rs::erasure::Technique;
+    use jerasure_rs::galois::{GaloisFieldBuilder, MultType, RegionType};
 
+    let altmap = GaloisFieldBuilder::new()
+        .w(CodeWord::W16)
+        .mult_type(MultType::SplitTable)
+        .region_type(RegionType::ALTMAP)
+        .args(16, 4)
+        .build()?;
+    let mut n_ok = 0;
+    for w in (0..=33).chain([64, 128]) {
+        let code_word = CodeWord::from_u8(w as u8);
+        let same = GaloisFieldBuilder::new().w(code_word).build().ok();
+        let other_w = GaloisFieldBuilder::new()
+            .w(if w == 8 { CodeWord::W16 } else { CodeWord::W8 })
+            .build()?;
+        let fields = [
+            (FieldSetup::Unset, None),
+            (FieldSetup::Same, same.as_ref()),
+            (FieldSetup::OtherW, Some(&other_w)),
+            (FieldSetup::AltMap, Some(&altmap).filter(|_| w == 16)),
+        ];
+        for (method, extra) in [
+            (ReedSolVand, Extra::Unset),
+            (ReedSolR6, Extra::Unset),
+            (Cauchy, Extra::Unset),
+            (CauchyOrig, Extra::Unset),
+            (CauchyXY, Extra::XyMissing),
+            (CauchyXY, Extra::XyValid),
+            (CauchyXY, Extra::XyDuplicate),
+            (Custom, Extra::CustomMissing),
+            (Custom, Extra::CustomMatrix),
+            (Custom, Extra::CustomBitMatrix),
+            (Liberation, Extra::Unset),
+            (Liber8tion, Extra::Unset),
+            (BlaumRoth, Extra::Unset),
+        ] {
+            for tech in [Matrix, BitMatrix, Schedule, ScheduleCache] {
+                for (k, m) in [-1, 1, 2, 3, 5, 8]
+                    .into_iter()
+                    .cartesian_product([-1, 1, 2, 3])
+                {
+                    for packet_size in [None, Some(-8), Some(12), Some(64)] {
+                        for (field_setup, field) in fields {
+                            if field_setup != FieldSetup::Unset && field.is_none() {
+                                continue;
+                            }
+                            let params = (w, k, m);
+                            let expect =
+                                expect_build(method, extra, field_setup, tech, params, packet_size);
+                            let actual = build_case(
+                                method,
+                                extra,
+                                field,
+                                field_setup,
+                                tech,
+                                params,
+                                packet_size,
+                            )?;
+                            assert_eq!(
+                                expect, actual,
+                                "method:{method:?} extra:{extra:?} field:{field_setup:?} \
+                                 tech:{tech:?} w:{w} k:{k} m:{m} packet_size:{packet_size:?}"
+                            );
+                            n_ok += usize::from(actual == Expect::Ok);
+                        }
+                    }
+                }
+            }
+        }
+    }
+    assert!(n_ok > 0);
+
+    // the cases out of the grid
+    for (method, extra, tech, params, packet_size, expect) in [
+        // the matrices must have at most i32::MAX elements
+        (
+            Cauchy,
+            Extra::Unset,
+            Matrix,
+            (32, 1 << 30, 1 << 30),
+            None,
+            Expect::InvalidArguments,
+        ),
+        (
+            Cauchy,
+            Extra::Unset,
+            BitMatrix,
+            (32, 1 << 12, 8),
+            Some(64),
+            Expect::InvalidArguments,
+        ),
+        // the MDS check of Custom covers at most 65536 erasure patterns
+        (
+            Custom,
+            Extra::CustomMatrix,
+            Matrix,
+            (8, 10, 9),
+            None,
+            Expect::NotSupported,
+        ),
+        (
+            Custom,
+            Extra::CustomMatrix,
+            Matrix,
+            (8, 9, 9),
+            None,
+            Expect::Ok,
+        ),
+    ] {
+        let actual = build_case(
+            method,
+            extra,
+            None,
+            FieldSetup::Unset,
+            tech,
+            params,
+            packet_size,
+        )?;
+        assert_eq!(expect, actual, "method:{method:?} tech:{tech:?} {params:?}");
+    }
     Ok(())
 }
 
@@ -103,26 +2026,40 @@ fn test_sechdule(k: i32, m: i32, method: CodingMethod) -> Result<(), Box<dyn std
         .tech(jerasure_rs::erasure::Technique::Schedule)
         .build()?;
     general_test(ec)?;
-    if m == 2 {
-        let ec = jerasure_rs::erasure::ErasureCodeBuilder::new()
-            .coding_method(method)
-            .k(NonZeroI32::new(k).unwrap())
-            .m(NonZeroI32::new(m).unwrap())
-            .packet_size(NonZeroI32::new(128).unwrap())
-            .tech(jerasure_rs::erasure::Technique::ScheduleCache)
-            .build()?;
-        general_test(ec)?;
-    }
+    let ec = jerasure_rs::erasure::ErasureCodeBuilder::new()
+        .coding_method(method)
+        .k(NonZeroI32::new(k).unwrap())
+        .m(NonZeroI32::new(m).unwrap())
+        .packet_size(NonZeroI32::new(128).unwrap())
+        .tech(jerasure_rs::erasure::Technique::ScheduleCache)
+        .build()?;
+    general_test(ec)?;
     Ok(())
 }
 
 fn general_test(ec: jerasure_rs::erasure::ErasureCode) -> Result<(), Box<dyn std::error::Error>> {
+    general_test_with(ec, BLK_SIZE)
+}
+
+/// The block size below `BLK_SIZE` which is a multiple of `packet_size * w`,
+/// as the techniques other than `Matrix` require.
+fn aligned_blk_size(ec: &jerasure_rs::erasure::ErasureCode) -> usize {
+    ec.packet_size().map_or(BLK_SIZE, |packet_size| {
+        let stripe = packet_size as usize * ec.w().to_u8() as usize;
+        BLK_SIZE / stripe * stripe
+    })
+}
+
+fn general_test_with(
+    ec: jerasure_rs::erasure::ErasureCode,
+    blk_size: usize,
+) -> Result<(), Box<dyn std::error::Error>> {
     let k = ec.k();
     let m = ec.m();
-    let source_k = make_rand_blk(k.try_into().unwrap(), BLK_SIZE);
+    let source_k = make_rand_blk(k.try_into().unwrap(), blk_size);
     // # encode
     let data = source_k.clone();
-    let mut encoded = make_zero_blk(m.try_into().unwrap(), BLK_SIZE);
+    let mut encoded = make_zero_blk(m.try_into().unwrap(), blk_size);
     ec.encode(&data, &mut encoded)?;
     let encoded = encoded;
     // encode should not modify source data
@@ -133,7 +2070,7 @@ fn general_test(ec: jerasure_rs::erasure::ErasureCode) -> Result<(), Box<dyn std
     let erase_idx = rand::random_range(0..k.try_into().unwrap());
     let mut erased_data = data.clone();
     let mut erased_code = encoded.clone();
-    erased_data[erase_idx] = vec![0_u8; BLK_SIZE];
+    erased_data[erase_idx] = vec![0_u8; blk_size];
     ec.decode(&mut erased_data, &mut erased_code, &[erase_idx as i32])?;
     assert_eq!(erased_data, data);
     assert_eq!(erased_code, encoded);
@@ -141,7 +2078,7 @@ fn general_test(ec: jerasure_rs::erasure::ErasureCode) -> Result<(), Box<dyn std
     let erase_idx = rand::random_range(0..m.try_into().unwrap());
     let mut erased_data = data.clone();
     let mut erased_code = encoded.clone();
-    erased_code[erase_idx] = vec![0_u8; BLK_SIZE];
+    erased_code[erase_idx] = vec![0_u8; blk_size];
     ec.decode(
         &mut erased_data,
         &mut erased_code,
@@ -159,9 +2096,9 @@ fn general_test(ec: jerasure_rs::erasure::ErasureCode) -> Result<(), Box<dyn std
     };
     erase_idx.iter().for_each(|i| {
         if i < &k {
-            erased_data[*i as usize] = vec![0_u8; BLK_SIZE];
+            erased_data[*i as usize] = vec![0_u8; blk_size];
         } else {
-            erased_code[(*i - k) as usize] = vec![0_u8; BLK_SIZE];
+            erased_code[(*i - k) as usize] = vec![0_u8; blk_size];
         }
     });
     ec.decode(&mut erased_data, &mut erased_code, &erase_idx)?;
@@ -171,6 +2108,38 @@ fn general_test(ec: jerasure_rs::erasure::ErasureCode) -> Result<(), Box<dyn std
     Ok(())
 }
 
+/// Encodes random data, then erases and recovers every single and double erasure pattern.
+fn double_erasure_test(
+    ec: jerasure_rs::erasure::ErasureCode,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let blk_size = 1 << 16;
+    let k = ec.k();
+    let m = ec.m();
+    let data = make_rand_blk(k.try_into().unwrap(), blk_size);
+    let mut encoded = make_zero_blk(m.try_into().unwrap(), blk_size);
+    ec.encode(&data, &mut encoded)?;
+
+    for i in 0..k + m {
+        for j in i..k + m {
+            let erase_idx = if i == j { vec![i] } else { vec![i, j] };
+            let mut erased_data = data.clone();
+            let mut erased_code = encoded.clone();
+            for &idx in erase_idx.iter() {
+                if idx < k {
+                    erased_data[idx as usize] = vec![0_u8; blk_size];
+                } else {
+                    erased_code[(idx - k) as usize] = vec![0_u8; blk_size];
+                }
+            }
+            ec.decode(&mut erased_data, &mut erased_code, &erase_idx)?;
+            assert_eq!(erased_data, data, "erased: {erase_idx:?}");
+            assert_eq!(erased_code, encoded, "erased: {erase_idx:?}");
+        }
+    }
+
+    Ok(())
+}
+
 fn fail_test(k: i32, m: i32, method: CodingMethod) {
     // # k <0
     let ec = jerasure_rs::erasure::ErasureCodeBuilder::new()
@@ -241,7 +2210,7 @@ fn fail_test(k: i32, m: i32, method: CodingMethod) {
         assert!(matches!(ec, Err(jerasure_rs::Error::InvalidArguments(_))));
 
         // # schedule cache
-        // m != 2
+        // m != 2 is supported as long as the coding method supports it
         let ec = jerasure_rs::erasure::ErasureCodeBuilder::new()
             .coding_method(method)
             .k(NonZeroI32::new(k).unwrap())
@@ -249,7 +2218,10 @@ fn fail_test(k: i32, m: i32, method: CodingMethod) {
             .packet_size(NonZeroI32::new(128).unwrap())
             .tech(jerasure_rs::erasure::Technique::ScheduleCache)
             .build();
-        assert!(matches!(ec, Err(jerasure_rs::Error::NotSupported(_))));
+        match method {
+            CodingMethod::Cauchy | CodingMethod::CauchyOrig => assert!(ec.is_ok()),
+            _ => assert!(matches!(ec, Err(jerasure_rs::Error::NotSupported(_)))),
+        }
         if m == 2 {
             // packet size < 0
             let ec = jerasure_rs::erasure::ErasureCodeBuilder::new()
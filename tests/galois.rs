@@ -69,3 +69,615 @@ fn test_w8_region_xor() {
     assert_eq!(buf, expect_out);
     assert_eq!(acc, src_b);
 }
+
+#[test]
+fn test_region_mult_all_w() {
+    const N: usize = 64;
+    for w in 1..=32_u8 {
+        let gf =
+            jerasure_rs::galois::GaloisField::try_from_code_word(jerasure_rs::CodeWord::from_u8(w))
+                .unwrap();
+        let size = match w {
+            1..=8 => 1,
+            9..=16 => 2,
+            _ => 4,
+        };
+        let mask = u32::MAX >> (32 - u32::from(w));
+        let load = |buf: &[u8], i: usize| {
+            let mut bytes = [0_u8; 4];
+            bytes[..size].copy_from_slice(&buf[i * size..(i + 1) * size]);
+            let elem = match size {
+                1 => u32::from(bytes[0]),
+                2 => u32::from(u16::from_ne_bytes([bytes[0], bytes[1]])),
+                _ => u32::from_ne_bytes(bytes),
+            };
+            elem & mask
+        };
+        let mut src = vec![0_u8; N * size];
+        for i in 0..N {
+            let elem = rand::random::<u32>() & mask;
+            let bytes = match size {
+                1 => vec![elem as u8],
+                2 => (elem as u16).to_ne_bytes().to_vec(),
+                _ => elem.to_ne_bytes().to_vec(),
+            };
+            src[i * size..(i + 1) * size].copy_from_slice(&bytes);
+        }
+        for multiply_by in [0, 1, 2, (rand::random::<u32>() & mask) as i32] {
+            // without accumulation
+            let mut dest: Vec<u8> = rand::random_iter().take(N * size).collect();
+            gf.region_multiply(&src, multiply_by, 0, &mut dest).unwrap();
+            for i in 0..N {
+                let expect = gf.multiply(load(&src, i) as i32, multiply_by) as u32 & mask;
+                assert_eq!(
+                    load(&dest, i),
+                    expect,
+                    "w = {w}, multiply_by = {multiply_by}"
+                );
+            }
+            // with accumulation
+            let acc: Vec<u8> = rand::random_iter().take(N * size).collect();
+            let mut dest = acc.clone();
+            gf.region_multiply(&src, multiply_by, 1, &mut dest).unwrap();
+            for i in 0..N {
+                let expect = gf.multiply(load(&src, i) as i32, multiply_by) as u32 & mask;
+                assert_eq!(
+                    load(&dest, i),
+                    expect ^ load(&acc, i),
+                    "w = {w}, multiply_by = {multiply_by}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_builder() {
+    use jerasure_rs::CodeWord;
+    use jerasure_rs::galois::{DivideType, GaloisField, GaloisFieldBuilder, MultType, RegionType};
+    for (w, mult_type, region_type, divide_type, args) in [
+        (
+            4,
+            MultType::Table,
+            RegionType::DEFAULT,
+            DivideType::Default,
+            (0, 0),
+        ),
+        (
+            7,
+            MultType::Shift,
+            RegionType::DEFAULT,
+            DivideType::Euclid,
+            (0, 0),
+        ),
+        (
+            8,
+            MultType::Default,
+            RegionType::DEFAULT,
+            DivideType::Default,
+            (0, 0),
+        ),
+        (
+            8,
+            MultType::Table,
+            RegionType::DOUBLE_TABLE,
+            DivideType::Default,
+            (0, 0),
+        ),
+        (
+            8,
+            MultType::LogZeroExt,
+            RegionType::DEFAULT,
+            DivideType::Default,
+            (0, 0),
+        ),
+        (
+            8,
+            MultType::BytwoB,
+            RegionType::NOSIMD,
+            DivideType::Matrix,
+            (0, 0),
+        ),
+        (
+            8,
+            MultType::SplitTable,
+            RegionType::DEFAULT,
+            DivideType::Default,
+            (8, 4),
+        ),
+        (
+            12,
+            MultType::LogTable,
+            RegionType::DEFAULT,
+            DivideType::Default,
+            (0, 0),
+        ),
+        (
+            16,
+            MultType::LogZero,
+            RegionType::DEFAULT,
+            DivideType::Default,
+            (0, 0),
+        ),
+        (
+            16,
+            MultType::Group,
+            RegionType::DEFAULT,
+            DivideType::Default,
+            (4, 4),
+        ),
+        (
+            16,
+            MultType::SplitTable,
+            RegionType::DEFAULT,
+            DivideType::Default,
+            (16, 8),
+        ),
+        (
+            32,
+            MultType::Shift,
+            RegionType::DEFAULT,
+            DivideType::Default,
+            (0, 0),
+        ),
+        (
+            32,
+            MultType::BytwoP,
+            RegionType::NOSIMD,
+            DivideType::Euclid,
+            (0, 0),
+        ),
+        (
+            32,
+            MultType::SplitTable,
+            RegionType::DEFAULT,
+            DivideType::Default,
+            (32, 8),
+        ),
+    ] {
+        let w = CodeWord::from_u8(w);
+        let gf = GaloisFieldBuilder::new()
+            .w(w)
+            .mult_type(mult_type)
+            .region_type(region_type)
+            .divide_type(divide_type)
+            .args(args.0, args.1)
+            .build()
+            .unwrap();
+        assert_eq!(gf.get_w().to_u8(), w.to_u8());
+        let default = GaloisField::try_from_code_word(w).unwrap();
+        let mask = u32::MAX >> (32 - u32::from(w.to_u8()));
+        for _ in 0..100 {
+            let a = (rand::random::<u32>() & mask) as i32;
+            let b = ((rand::random::<u32>() & mask).max(1)) as i32;
+            assert_eq!(gf.multiply(a, b), default.multiply(a, b), "{mult_type:?}");
+            assert_eq!(gf.divide(a, b), default.divide(a, b), "{mult_type:?}");
+            assert_eq!(gf.inverse(b), default.inverse(b), "{mult_type:?}");
+        }
+        if matches!(w, CodeWord::W8 | CodeWord::W16 | CodeWord::W32) {
+            let src: Vec<u8> = rand::random_iter().take(256).collect();
+            let multiply_by = (rand::random::<u32>() & mask) as i32;
+            let mut expect = vec![0_u8; 256];
+            default
+                .region_multiply(&src, multiply_by, 0, &mut expect)
+                .unwrap();
+            let mut out = vec![0_u8; 256];
+            gf.region_multiply(&src, multiply_by, 0, &mut out).unwrap();
+            assert_eq!(out, expect, "{mult_type:?}");
+            gf.region_multiply(&src, multiply_by, 1, &mut out).unwrap();
+            assert!(out.iter().all(|&b| b == 0), "{mult_type:?}");
+        }
+        // the field is shared by the clones
+        let cloned = gf.clone();
+        drop(gf);
+        assert_eq!(cloned.multiply(1, 1), 1);
+    }
+
+    // invalid techniques and arguments
+    for builder in [
+        GaloisFieldBuilder::new().w(CodeWord::Other(33)),
+        GaloisFieldBuilder::new().w(CodeWord::Other(0)),
+        // the regions of the other word sizes are not multiplied by gf-complete
+        GaloisFieldBuilder::new()
+            .w(CodeWord::Other(4))
+            .mult_type(MultType::Table)
+            .region_type(RegionType::DOUBLE_TABLE),
+        GaloisFieldBuilder::new()
+            .w(CodeWord::Other(7))
+            .region_type(RegionType::NOSIMD),
+    ] {
+        let res = builder.build();
+        assert!(matches!(res, Err(jerasure_rs::Error::NotSupported(_))));
+    }
+    for builder in [
+        GaloisFieldBuilder::new().args(1, 0),
+        GaloisFieldBuilder::new().region_type(RegionType::SIMD | RegionType::NOSIMD),
+        GaloisFieldBuilder::new()
+            .mult_type(MultType::Group)
+            .args(4, 4),
+        GaloisFieldBuilder::new()
+            .w(CodeWord::W16)
+            .mult_type(MultType::SplitTable)
+            .args(4, 4),
+        GaloisFieldBuilder::new()
+            .w(CodeWord::W32)
+            .mult_type(MultType::LogTable),
+        GaloisFieldBuilder::new()
+            .mult_type(MultType::Shift)
+            .region_type(RegionType::DOUBLE_TABLE),
+    ] {
+        let res = builder.build();
+        assert!(matches!(res, Err(jerasure_rs::Error::InvalidArguments(_))));
+    }
+    assert!((RegionType::SIMD | RegionType::ALTMAP).contains(RegionType::ALTMAP));
+}
+
+#[test]
+fn test_prim_poly() {
+    use jerasure_rs::CodeWord;
+    use jerasure_rs::galois::{GaloisField, GaloisFieldBuilder};
+
+    // the default polynomials of gf-complete, with and without the leading bit
+    for (w, poly) in [(8, 0x11d_u64), (16, 0x1100b), (32, 0x400007)] {
+        let w = CodeWord::from_u8(w);
+        let default = GaloisField::try_from_code_word(w).unwrap();
+        let full = poly | (1 << w.to_u8());
+        for poly in [full, full ^ (1 << w.to_u8())] {
+            let gf = GaloisFieldBuilder::new()
+                .w(w)
+                .prim_poly(poly)
+                .build()
+                .unwrap();
+            assert_eq!(gf.prim_poly(), Some(full));
+            let mask = u32::MAX >> (32 - u32::from(w.to_u8()));
+            for _ in 0..100 {
+                let a = (rand::random::<u32>() & mask) as i32;
+                let b = ((rand::random::<u32>() & mask).max(1)) as i32;
+                assert_eq!(gf.multiply(a, b), default.multiply(a, b));
+                assert_eq!(gf.divide(a, b), default.divide(a, b));
+            }
+        }
+    }
+    // the polynomial is filled in and compared as gf-complete uses it
+    let hash = |gf: &GaloisField| {
+        use std::hash::{DefaultHasher, Hash, Hasher};
+        let mut hasher = DefaultHasher::new();
+        gf.hash(&mut hasher);
+        hasher.finish()
+    };
+    let fields =
+        [0, 0x1d, 0x11d].map(|poly| GaloisFieldBuilder::new().prim_poly(poly).build().unwrap());
+    for gf in &fields {
+        assert_eq!(gf.prim_poly(), Some(0x11d));
+        assert_eq!(gf, &fields[0]);
+        assert_eq!(hash(gf), hash(&fields[0]));
+        let a = jerasure_rs::matrix::Matrix::new(gf.clone(), 1, 1, [2]).unwrap();
+        let b = jerasure_rs::matrix::Matrix::new(fields[0].clone(), 1, 1, [3]).unwrap();
+        assert_eq!(a.multiply(&b).unwrap().as_slice(), &[6]);
+    }
+    // gf-complete cannot create the field of w = 31 with a polynomial set
+    for w in (1..=30).chain([32, 64, 128]).map(CodeWord::from_u8) {
+        let default = GaloisFieldBuilder::new().w(w).build().unwrap();
+        let poly = default.prim_poly().unwrap();
+        let explicit = GaloisFieldBuilder::new()
+            .w(w)
+            .prim_poly(poly)
+            .build()
+            .unwrap();
+        assert_eq!(explicit, default, "w:{}", w.to_u8());
+    }
+
+    // x^8 + x^5 + x^3 + x^2 + 1, multiplying by 2 is shifting and reducing by the polynomial
+    let gf = GaloisFieldBuilder::new().prim_poly(0x12d).build().unwrap();
+    let mut e = 1;
+    for i in 1..=255 {
+        let expect = match e << 1 {
+            shifted if shifted & 0x100 != 0 => shifted ^ 0x12d,
+            shifted => shifted,
+        };
+        e = gf.multiply(e, 2);
+        assert_eq!(e, expect);
+        // 2 is a generator of the multiplicative group
+        assert_eq!(e == 1, i == 255);
+    }
+    let mut bytes: Vec<u8> = rand::random_iter().take(64).collect();
+    let src = bytes.clone();
+    gf.region_multiply(&src, 2, 0, &mut bytes).unwrap();
+    for (&s, &d) in src.iter().zip(&bytes) {
+        assert_eq!(i32::from(d), gf.multiply(i32::from(s), 2));
+    }
+
+    // the polynomials of the wrong degree, reducible, or irreducible but not primitive
+    for (w, poly) in [
+        (8, 0x31d),
+        (8, 0x1_0000_011d),
+        (8, 0x100),
+        (8, 0x105),
+        (8, 0x11b),
+        (4, 0x1f),
+        (16, 0x10000),
+    ] {
+        let res = GaloisFieldBuilder::new()
+            .w(CodeWord::from_u8(w))
+            .prim_poly(poly)
+            .build();
+        assert!(
+            matches!(res, Err(jerasure_rs::Error::InvalidArguments(_))),
+            "{poly:#x}"
+        );
+    }
+}
+
+#[test]
+fn test_wide_fields() {
+    use jerasure_rs::CodeWord;
+    use jerasure_rs::galois::{GaloisField, GaloisFieldBuilder};
+
+    // the product of `a` and `b` modulo the polynomial of degree w whose lower bits are `low`
+    let reference = |mut a: u128, mut b: u128, w: u32, low: u128| {
+        let mut r = 0;
+        while b != 0 {
+            if b & 1 == 1 {
+                r ^= a;
+            }
+            b >>= 1;
+            let carry = (a >> (w - 1)) & 1 == 1;
+            a = (a << 1) & (u128::MAX >> (128 - w));
+            if carry {
+                a ^= low;
+            }
+        }
+        r
+    };
+
+    let gf = GaloisField::try_from_code_word(CodeWord::W64).unwrap();
+    assert!(matches!(gf.get_w(), CodeWord::W64));
+    for _ in 0..100 {
+        let a = rand::random::<u64>();
+        let b = rand::random::<u64>().max(1);
+        let prod = gf.multiply_u64(a, b);
+        assert_eq!(u128::from(prod), reference(a.into(), b.into(), 64, 0x1b));
+        assert_eq!(gf.divide_u64(prod, b), a);
+        assert_eq!(gf.multiply_u64(b, gf.inverse_u64(b)), 1);
+    }
+    let gf = GaloisField::try_from_code_word(CodeWord::W128).unwrap();
+    assert!(matches!(gf.get_w(), CodeWord::W128));
+    for _ in 0..100 {
+        let a = rand::random::<u128>();
+        let b = rand::random::<u128>().max(1);
+        let prod = gf.multiply_u128(a, b);
+        assert_eq!(prod, reference(a, b, 128, 0x87));
+        assert_eq!(gf.divide_u128(prod, b), a);
+        assert_eq!(gf.multiply_u128(b, gf.inverse_u128(b)), 1);
+    }
+
+    // region multiplication
+    const N: usize = 256;
+    let src: Vec<u8> = rand::random_iter().take(N).collect();
+    let acc: Vec<u8> = rand::random_iter().take(N).collect();
+    let gf = GaloisField::try_from_code_word(CodeWord::W64).unwrap();
+    let multiply_by = rand::random::<u64>();
+    let mut dest = acc.clone();
+    gf.region_multiply_u64(&src, multiply_by, 1, &mut dest)
+        .unwrap();
+    for ((s, a), d) in src.chunks(8).zip(acc.chunks(8)).zip(dest.chunks(8)) {
+        let load = |b: &[u8]| u64::from_ne_bytes(b.try_into().unwrap());
+        assert_eq!(load(d), gf.multiply_u64(load(s), multiply_by) ^ load(a));
+    }
+    let mut out = vec![0_u8; N];
+    gf.region_multiply(&src, 0x1234, 0, &mut out).unwrap();
+    let mut expect = vec![0_u8; N];
+    gf.region_multiply_u64(&src, 0x1234, 0, &mut expect)
+        .unwrap();
+    assert_eq!(out, expect);
+
+    let gf = GaloisField::try_from_code_word(CodeWord::W128).unwrap();
+    let multiply_by = rand::random::<u128>();
+    let mut dest = acc.clone();
+    gf.region_multiply_u128(&src, multiply_by, 1, &mut dest)
+        .unwrap();
+    // the upper 64 bits are stored first
+    let load = |b: &[u8]| {
+        let hi = u64::from_ne_bytes(b[..8].try_into().unwrap());
+        let lo = u64::from_ne_bytes(b[8..].try_into().unwrap());
+        (u128::from(hi) << 64) | u128::from(lo)
+    };
+    for ((s, a), d) in src.chunks(16).zip(acc.chunks(16)).zip(dest.chunks(16)) {
+        assert_eq!(load(d), gf.multiply_u128(load(s), multiply_by) ^ load(a));
+    }
+    let res = gf.region_multiply_u128(&src[..8], multiply_by, 0, &mut dest[..8]);
+    assert!(matches!(res, Err(jerasure_rs::Error::InvalidArguments(_))));
+    let gf8 = GaloisField::try_from_code_word(CodeWord::W8).unwrap();
+    let res = gf8.region_multiply_u64(&src, 1, 0, &mut dest);
+    assert!(matches!(res, Err(jerasure_rs::Error::NotSupported(_))));
+    let res = jerasure_rs::matrix::Matrix::new(gf, 1, 1, [1]);
+    assert!(matches!(res, Err(jerasure_rs::Error::NotSupported(_))));
+
+    // the polynomials of gf-complete are primitive, and x^w + 1 is reducible
+    for (w, poly) in [(CodeWord::W64, 0x1b), (CodeWord::W128, 0x87)] {
+        let gf = GaloisFieldBuilder::new()
+            .w(w)
+            .prim_poly(poly)
+            .build()
+            .unwrap();
+        assert_eq!(gf.prim_poly(), Some(poly));
+        let res = GaloisFieldBuilder::new().w(w).prim_poly(1).build();
+        assert!(matches!(res, Err(jerasure_rs::Error::InvalidArguments(_))));
+    }
+    let res = GaloisFieldBuilder::new().w(CodeWord::Other(48)).build();
+    assert!(matches!(res, Err(jerasure_rs::Error::NotSupported(_))));
+}
+
+#[test]
+fn test_wide_fields_narrow_ops() {
+    use jerasure_rs::CodeWord;
+    use jerasure_rs::galois::GaloisField;
+    // the operands are zero-extended, and the results are truncated
+    let gf = GaloisField::try_from_code_word(CodeWord::W64).unwrap();
+    for _ in 0..100 {
+        let (a, b) = (rand::random::<i32>(), rand::random::<i32>().max(1));
+        let (wa, wb) = (u64::from(a as u32), u64::from(b as u32));
+        assert_eq!(gf.multiply(a, b), gf.multiply_u64(wa, wb) as i32);
+        assert_eq!(gf.divide(a, b), gf.divide_u64(wa, wb) as i32);
+        assert_eq!(gf.inverse(b), gf.inverse_u64(wb) as i32);
+    }
+    assert_eq!(gf.multiply(-1, 2), gf.multiply_u64(0xffff_ffff, 2) as i32);
+    let gf = GaloisField::try_from_code_word(CodeWord::W128).unwrap();
+    for _ in 0..100 {
+        let (a, b) = (rand::random::<i32>(), rand::random::<i32>().max(1));
+        let (wa, wb) = (u128::from(a as u32), u128::from(b as u32));
+        assert_eq!(gf.multiply(a, b), gf.multiply_u128(wa, wb) as i32);
+        assert_eq!(gf.divide(a, b), gf.divide_u128(wa, wb) as i32);
+        assert_eq!(gf.inverse(b), gf.inverse_u128(wb) as i32);
+    }
+}
+
+#[test]
+fn test_composite_field() {
+    use jerasure_rs::CodeWord;
+    use jerasure_rs::galois::{GaloisField, GaloisFieldBuilder, MultType, RegionType};
+    let composite = |w: CodeWord, base: &GaloisField| {
+        GaloisFieldBuilder::new()
+            .w(w)
+            .mult_type(MultType::Composite)
+            .args(2, 0)
+            .base_field(base.clone())
+    };
+
+    // GF((2^16)^2) modulo x^2 + 2x + 1, the default one over the default field of w = 16
+    let base = GaloisFieldBuilder::new().w(CodeWord::W16).build().unwrap();
+    let gf = composite(CodeWord::W32, &base).build().unwrap();
+    assert_eq!(gf.prim_poly(), Some(2));
+    assert_eq!(gf.base_field(), Some(&base));
+    let explicit = composite(CodeWord::W32, &base)
+        .prim_poly(2)
+        .build()
+        .unwrap();
+    assert_eq!(explicit.prim_poly(), Some(2));
+    assert_eq!(explicit, gf);
+    let default = GaloisField::try_from_code_word(CodeWord::W32).unwrap();
+    assert_eq!(base.base_field(), None);
+    assert_ne!(gf, default);
+    let split = |a: i32| (a & 0xffff, (a >> 16) & 0xffff);
+    for _ in 0..100 {
+        let a = rand::random::<i32>();
+        let b = rand::random::<i32>() | 1;
+        let ((a0, a1), (b0, b1)) = (split(a), split(b));
+        let a1b1 = base.multiply(a1, b1);
+        let low = base.multiply(a0, b0) ^ a1b1;
+        let high = base.multiply(a1, b0) ^ base.multiply(a0, b1) ^ base.multiply(a1b1, 2);
+        let prod = gf.multiply(a, b);
+        assert_eq!(prod, (high << 16) | low);
+        assert_eq!(explicit.multiply(a, b), prod);
+        assert_eq!(gf.divide(prod, b), a);
+        assert_eq!(gf.multiply(b, gf.inverse(b)), 1);
+    }
+    let src: Vec<u8> = rand::random_iter().take(256).collect();
+    let multiply_by = rand::random::<i32>();
+    let mut out = vec![0_u8; 256];
+    gf.region_multiply(&src, multiply_by, 0, &mut out).unwrap();
+    for (s, d) in src.chunks_exact(4).zip(out.chunks_exact(4)) {
+        let s = i32::from_ne_bytes(s.try_into().unwrap());
+        assert_eq!(
+            i32::from_ne_bytes(d.try_into().unwrap()),
+            gf.multiply(s, multiply_by)
+        );
+    }
+
+    // the composite fields nest, and build GF(2^8) and GF(2^64) as well
+    let gf_64 = composite(CodeWord::W64, &gf).build().unwrap();
+    let gf_8 = composite(
+        CodeWord::W8,
+        &GaloisFieldBuilder::new()
+            .w(CodeWord::Other(4))
+            .build()
+            .unwrap(),
+    )
+    .build()
+    .unwrap();
+    for _ in 0..100 {
+        let a = rand::random::<u64>().max(1);
+        assert_eq!(gf_64.multiply_u64(a, gf_64.inverse_u64(a)), 1);
+        let a = i32::from(rand::random::<u8>().max(1));
+        assert_eq!(gf_8.multiply(a, gf_8.inverse(a)), 1);
+    }
+
+    // the base field is missing, of the wrong size, the default field of Jerasure,
+    // or set for another technique, and the polynomials that are reducible or too large
+    for builder in [
+        GaloisFieldBuilder::new()
+            .w(CodeWord::W32)
+            .mult_type(MultType::Composite)
+            .args(2, 0),
+        composite(CodeWord::W16, &base),
+        composite(
+            CodeWord::W32,
+            &GaloisField::try_from_code_word(CodeWord::W16).unwrap(),
+        ),
+        GaloisFieldBuilder::new()
+            .w(CodeWord::W32)
+            .base_field(base.clone()),
+        composite(CodeWord::W32, &base).prim_poly(1),
+        composite(CodeWord::W32, &base).prim_poly(0x10000),
+        composite(CodeWord::W32, &base).args(0, 0),
+        composite(CodeWord::W32, &base).region_type(RegionType::CAUCHY),
+    ] {
+        let res = builder.build();
+        assert!(matches!(res, Err(jerasure_rs::Error::InvalidArguments(_))));
+    }
+}
+
+#[test]
+fn test_shared_field_threads() {
+    use jerasure_rs::CodeWord;
+    use jerasure_rs::galois::{GaloisField, GaloisFieldBuilder, MultType};
+
+    // the group multiplication writes its shift table to the scratch memory of the field
+    let gf = GaloisFieldBuilder::new()
+        .w(CodeWord::W32)
+        .mult_type(MultType::Group)
+        .args(4, 4)
+        .build()
+        .unwrap();
+    let default = GaloisField::try_from_code_word(CodeWord::W32).unwrap();
+    let pairs: Vec<(i32, i32)> = (0..1000).map(|_| rand::random()).collect();
+    let expect: Vec<i32> = pairs.iter().map(|&(a, b)| default.multiply(a, b)).collect();
+    std::thread::scope(|s| {
+        for _ in 0..4 {
+            s.spawn(|| {
+                for _ in 0..100 {
+                    let prods: Vec<i32> = pairs.iter().map(|&(a, b)| gf.multiply(a, b)).collect();
+                    assert_eq!(prods, expect);
+                }
+            });
+        }
+    });
+
+    // the default region multiplication of w=64 writes its lazy tables to the scratch memory
+    const N: usize = 4096;
+    let gf = GaloisField::try_from_code_word(CodeWord::W64).unwrap();
+    let src: Vec<u8> = rand::random_iter().take(N).collect();
+    let factors: Vec<u64> = (0..4).map(|_| rand::random()).collect();
+    let expect: Vec<Vec<u8>> = factors
+        .iter()
+        .map(|&multiply_by| {
+            let mut dest = vec![0_u8; N];
+            gf.region_multiply_u64(&src, multiply_by, 0, &mut dest)
+                .unwrap();
+            dest
+        })
+        .collect();
+    std::thread::scope(|s| {
+        for (&multiply_by, expect) in factors.iter().zip(&expect) {
+            let (gf, src) = (&gf, &src);
+            s.spawn(move || {
+                let mut dest = vec![0_u8; N];
+                for _ in 0..100 {
+                    gf.region_multiply_u64(src, multiply_by, 0, &mut dest)
+                        .unwrap();
+                    assert_eq!(&dest, expect);
+                }
+            });
+        }
+    });
+}
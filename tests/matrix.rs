@@ -0,0 +1,341 @@
+use jerasure_rs::CodeWord;
+use jerasure_rs::galois::{GaloisField, GaloisFieldBuilder};
+use jerasure_rs::matrix::Matrix;
+
+fn gf(w: CodeWord) -> GaloisField {
+    GaloisField::try_from_code_word(w).unwrap()
+}
+
+#[test]
+fn test_ctor() {
+    let mat = Matrix::new(gf(CodeWord::W8), 2, 3, [1, 2, 3, 4, 5, 6]).unwrap();
+    assert_eq!(mat.rows(), 2);
+    assert_eq!(mat.cols(), 3);
+    assert_eq!(mat.w(), 8);
+    assert_eq!(mat.get(1, 2), 6);
+    assert_eq!(mat.row(1), &[4, 5, 6]);
+    assert_eq!(mat.as_slice(), &[1, 2, 3, 4, 5, 6]);
+    // wrong number of elements
+    let mat = Matrix::new(gf(CodeWord::W8), 2, 3, [1, 2, 3, 4, 5]);
+    assert!(matches!(mat, Err(jerasure_rs::Error::InvalidArguments(_))));
+    // element out of range
+    let mat = Matrix::new(gf(CodeWord::W8), 1, 2, [1, 256]);
+    assert!(matches!(mat, Err(jerasure_rs::Error::InvalidArguments(_))));
+    let mat = Matrix::new(gf(CodeWord::Other(4)), 1, 2, [1, -1]);
+    assert!(matches!(mat, Err(jerasure_rs::Error::InvalidArguments(_))));
+    // any 32-bit word is in GF(2^32)
+    let mat = Matrix::new(gf(CodeWord::W32), 1, 2, [1, -1]);
+    assert!(mat.is_ok());
+
+    let id = Matrix::identity(gf(CodeWord::W8), 3).unwrap();
+    assert_eq!(id.as_slice(), &[1, 0, 0, 0, 1, 0, 0, 0, 1]);
+    let zeros = Matrix::zeros(gf(CodeWord::W8), 2, 2).unwrap();
+    assert_eq!(zeros.as_slice(), &[0; 4]);
+    for w in [CodeWord::W64, CodeWord::W128] {
+        assert!(matches!(
+            Matrix::identity(gf(w), 3),
+            Err(jerasure_rs::Error::NotSupported(_))
+        ));
+        assert!(matches!(
+            Matrix::zeros(gf(w), 2, 2),
+            Err(jerasure_rs::Error::NotSupported(_))
+        ));
+    }
+}
+
+#[test]
+fn test_vandermonde() {
+    let mat = Matrix::vandermonde(gf(CodeWord::W8), 4, 3).unwrap();
+    assert_eq!(mat.row(0), &[1, 0, 0]);
+    assert_eq!(mat.row(1), &[1, 1, 1]);
+    assert_eq!(mat.row(2), &[1, 2, 4]);
+    assert_eq!(mat.row(3), &[1, 3, 5]);
+    // any `cols` rows are independent
+    for rows in [[0, 1, 2], [0, 1, 3], [0, 2, 3], [1, 2, 3]] {
+        let sub = mat.submatrix(&rows, &[0, 1, 2]).unwrap();
+        assert!(sub.is_invertible(), "rows:{rows:?}");
+    }
+    // too many rows
+    let mat = Matrix::vandermonde(gf(CodeWord::Other(2)), 5, 2);
+    assert!(matches!(mat, Err(jerasure_rs::Error::InvalidArguments(_))));
+}
+
+#[test]
+fn test_cauchy() {
+    let gf = gf(CodeWord::W8);
+    let mat = Matrix::cauchy(gf.clone(), &[0, 1], &[2, 3, 4]).unwrap();
+    assert_eq!(mat.rows(), 2);
+    assert_eq!(mat.cols(), 3);
+    assert_eq!(mat.get(1, 2), gf.inverse(1 ^ 4));
+    // every square submatrix is invertible
+    for i in 0..2 {
+        for j in 0..3 {
+            assert_ne!(mat.get(i, j), 0);
+        }
+    }
+    assert!(mat.submatrix(&[0, 1], &[0, 2]).unwrap().is_invertible());
+    // not distinct
+    let mat = Matrix::cauchy(gf.clone(), &[0, 1], &[1, 3]);
+    assert!(matches!(mat, Err(jerasure_rs::Error::InvalidArguments(_))));
+    // out of range
+    let mat = Matrix::cauchy(gf, &[0, 1], &[2, 256]);
+    assert!(matches!(mat, Err(jerasure_rs::Error::InvalidArguments(_))));
+}
+
+#[test]
+fn test_algebra() {
+    for w in [
+        CodeWord::W8,
+        CodeWord::W16,
+        CodeWord::W32,
+        CodeWord::Other(5),
+    ] {
+        let gf = gf(w);
+        let x = Vec::from_iter(0..4);
+        let y = Vec::from_iter(4..8);
+        let mat = Matrix::cauchy(gf.clone(), &x, &y).unwrap();
+        let id = Matrix::identity(gf.clone(), 4).unwrap();
+
+        // multiply
+        assert_eq!(mat.multiply(&id).unwrap(), mat);
+        assert_eq!(id.multiply(&mat).unwrap(), mat);
+        let a = Matrix::new(gf.clone(), 1, 2, [1, 2]).unwrap();
+        let b = Matrix::new(gf.clone(), 2, 1, [3, 4]).unwrap();
+        let ab = a.multiply(&b).unwrap();
+        assert_eq!(
+            ab.as_slice(),
+            &[gf.add(gf.multiply(1, 3), gf.multiply(2, 4))]
+        );
+        assert!(matches!(
+            a.multiply(&a),
+            Err(jerasure_rs::Error::InvalidArguments(_))
+        ));
+
+        // transpose
+        let t = mat.transpose();
+        assert_eq!(t.get(1, 3), mat.get(3, 1));
+        assert_eq!(t.transpose(), mat);
+        assert_eq!(
+            a.transpose(),
+            Matrix::new(gf.clone(), 2, 1, [1, 2]).unwrap()
+        );
+
+        // invert
+        let inv = mat.invert().unwrap();
+        assert_eq!(mat.multiply(&inv).unwrap(), id);
+        assert_eq!(inv.multiply(&mat).unwrap(), id);
+        assert_eq!(id.invert().unwrap(), id);
+        assert!(mat.is_invertible());
+
+        // rank and determinant
+        assert_eq!(mat.rank(), 4);
+        assert_ne!(mat.det().unwrap(), 0);
+        assert_eq!(id.det().unwrap(), 1);
+        // det(AB) = det(A)det(B)
+        let vand = Matrix::vandermonde(gf.clone(), 4, 4).unwrap();
+        assert_eq!(
+            mat.multiply(&vand).unwrap().det().unwrap(),
+            gf.multiply(mat.det().unwrap(), vand.det().unwrap())
+        );
+        // det(A^-1) = 1 / det(A)
+        assert_eq!(inv.det().unwrap(), gf.inverse(mat.det().unwrap()));
+        let diag = Matrix::new(gf.clone(), 2, 2, [3, 0, 0, 7]).unwrap();
+        assert_eq!(diag.det().unwrap(), gf.multiply(3, 7));
+
+        // singular
+        let mut data = mat.as_slice().to_vec();
+        data.copy_within(0..4, 4);
+        let singular = Matrix::new(gf.clone(), 4, 4, data).unwrap();
+        assert_eq!(singular.rank(), 3);
+        assert_eq!(singular.det().unwrap(), 0);
+        assert!(!singular.is_invertible());
+        assert!(matches!(
+            singular.invert(),
+            Err(jerasure_rs::Error::InvalidArguments(_))
+        ));
+        assert_eq!(Matrix::zeros(gf.clone(), 3, 2).unwrap().rank(), 0);
+        assert_eq!(a.rank(), 1);
+
+        // not square
+        assert!(!a.is_invertible());
+        assert!(matches!(
+            a.invert(),
+            Err(jerasure_rs::Error::InvalidArguments(_))
+        ));
+        assert!(matches!(
+            a.det(),
+            Err(jerasure_rs::Error::InvalidArguments(_))
+        ));
+    }
+
+    // matrices of the same w in fields of other polynomials
+    let gf_11d = GaloisFieldBuilder::new().prim_poly(0x11d).build().unwrap();
+    let gf_12b = GaloisFieldBuilder::new().prim_poly(0x12b).build().unwrap();
+    let a = Matrix::new(gf_11d.clone(), 2, 2, [1, 2, 3, 4]).unwrap();
+    let b = Matrix::new(gf_12b.clone(), 2, 2, [1, 2, 3, 4]).unwrap();
+    assert_ne!(a, b);
+    assert_ne!(
+        a,
+        Matrix::new(gf(CodeWord::W8), 2, 2, [1, 2, 3, 4]).unwrap()
+    );
+    assert_eq!(a, Matrix::new(gf_11d, 2, 2, [1, 2, 3, 4]).unwrap());
+    assert!(matches!(
+        a.multiply(&b),
+        Err(jerasure_rs::Error::InvalidArguments(_))
+    ));
+    assert!(b.multiply(&Matrix::identity(gf_12b, 2).unwrap()).is_ok());
+}
+
+#[test]
+fn test_submatrix() {
+    let mat = Matrix::new(gf(CodeWord::W8), 3, 3, [1, 2, 3, 4, 5, 6, 7, 8, 9]).unwrap();
+    let sub = mat.submatrix(&[2, 0], &[1, 2]).unwrap();
+    assert_eq!(sub.as_slice(), &[8, 9, 2, 3]);
+    assert_eq!(mat.submatrix(&[], &[]).unwrap().rows(), 0);
+    assert!(matches!(
+        mat.submatrix(&[3], &[0]),
+        Err(jerasure_rs::Error::InvalidArguments(_))
+    ));
+    assert!(matches!(
+        mat.submatrix(&[0], &[3]),
+        Err(jerasure_rs::Error::InvalidArguments(_))
+    ));
+}
+
+#[test]
+fn test_coding_matrix() -> Result<(), Box<dyn std::error::Error>> {
+    // a systematic generator matrix from a vandermonde matrix is MDS
+    let (k, m) = (4, 2);
+    let gf = gf(CodeWord::W8);
+    let vand = Matrix::vandermonde(gf.clone(), k + m, k)?;
+    let top = vand.submatrix(&Vec::from_iter(0..k), &Vec::from_iter(0..k))?;
+    let generator = vand.multiply(&top.invert()?)?;
+    let parity = generator.submatrix(&Vec::from_iter(k..k + m), &Vec::from_iter(0..k))?;
+    let ec = jerasure_rs::erasure::ErasureCodeBuilder::new()
+        .coding_method(jerasure_rs::erasure::CodingMethod::Custom)
+        .k(std::num::NonZeroI32::new(k as i32).unwrap())
+        .m(std::num::NonZeroI32::new(m as i32).unwrap())
+        .tech(jerasure_rs::erasure::Technique::Matrix)
+        .coding_matrix(parity.into_vec())
+        .build()?;
+    assert_eq!(ec.k(), k as i32);
+    Ok(())
+}
+
+#[test]
+fn test_bitmatrix() {
+    use jerasure_rs::matrix::BitMatrix;
+    for w in [CodeWord::W8, CodeWord::W16, CodeWord::Other(5)] {
+        let gf = gf(w);
+        let wu = usize::from(w.to_u8());
+        let mat = Matrix::cauchy(gf.clone(), &[0, 1, 2], &[3, 4, 5]).unwrap();
+        let bmat = BitMatrix::from(&mat);
+        assert_eq!(bmat.w(), w.to_u8());
+        assert_eq!(bmat.rows(), 3 * wu);
+        assert_eq!(bmat.cols(), 3 * wu);
+        // the block of 1 is the identity
+        let id = Matrix::identity(gf.clone(), 2).unwrap();
+        assert_eq!(BitMatrix::from(&id), BitMatrix::identity(w.to_u8(), 2));
+
+        // conversion back
+        assert_eq!(bmat.to_matrix(gf.clone()).unwrap(), mat);
+        let mut bits = bmat.clone().into_vec();
+        bits[1] ^= 1;
+        let not_converted = BitMatrix::new(w.to_u8(), 3 * wu, 3 * wu, bits).unwrap();
+        assert!(matches!(
+            not_converted.to_matrix(gf.clone()),
+            Err(jerasure_rs::Error::InvalidArguments(_))
+        ));
+
+        // multiplication and inversion commute with the conversion
+        let inv = mat.invert().unwrap();
+        let binv = bmat.invert().unwrap();
+        assert_eq!(binv, BitMatrix::from(&inv));
+        assert_eq!(
+            bmat.multiply(&binv).unwrap(),
+            BitMatrix::identity(w.to_u8(), 3)
+        );
+        let vand = Matrix::vandermonde(gf.clone(), 3, 2).unwrap();
+        assert_eq!(
+            bmat.multiply(&BitMatrix::from(&vand)).unwrap(),
+            BitMatrix::from(&mat.multiply(&vand).unwrap())
+        );
+        assert!(bmat.is_invertible());
+        assert!(!BitMatrix::from(&vand).is_invertible());
+        assert!(matches!(
+            BitMatrix::from(&vand).invert(),
+            Err(jerasure_rs::Error::InvalidArguments(_))
+        ));
+        assert!(matches!(
+            BitMatrix::from(&vand).multiply(&BitMatrix::from(&vand)),
+            Err(jerasure_rs::Error::InvalidArguments(_))
+        ));
+    }
+
+    // invalid bit-matrices
+    assert!(BitMatrix::new(2, 2, 4, [1, 0, 0, 1, 0, 1, 1, 0]).is_ok());
+    assert!(matches!(
+        BitMatrix::new(2, 2, 4, [1, 0, 0, 1, 0, 1, 1, 2]),
+        Err(jerasure_rs::Error::InvalidArguments(_))
+    ));
+    assert!(matches!(
+        BitMatrix::new(2, 2, 3, [1, 0, 0, 1, 0, 1]),
+        Err(jerasure_rs::Error::InvalidArguments(_))
+    ));
+    assert!(matches!(
+        BitMatrix::new(2, 2, 4, [1, 0, 0, 1]),
+        Err(jerasure_rs::Error::InvalidArguments(_))
+    ));
+    assert!(matches!(
+        BitMatrix::new(0, 0, 0, []),
+        Err(jerasure_rs::Error::InvalidArguments(_))
+    ));
+}
+
+#[test]
+fn test_bitmatrix_ones() -> Result<(), Box<dyn std::error::Error>> {
+    use jerasure_rs::erasure::{CodingMethod, ErasureCodeBuilder, Technique};
+    use jerasure_rs::matrix::BitMatrix;
+    // in GF(2^3) with the polynomial x^3 + x + 1, 2 is multiplied as:
+    // [0 0 1]
+    // [1 0 1]
+    // [0 1 0]
+    let gf3 = gf(CodeWord::Other(3));
+    let bmat = BitMatrix::from(&Matrix::new(gf3, 1, 2, [1, 2])?);
+    assert_eq!(
+        bmat.as_slice(),
+        &[
+            1, 0, 0, 0, 0, 1, //
+            0, 1, 0, 1, 0, 1, //
+            0, 0, 1, 0, 1, 0, //
+        ]
+    );
+    assert_eq!(bmat.n_ones(), 7);
+    assert_eq!(bmat.n_xors(), 4);
+    assert_eq!(bmat.to_string(), "100 001\n010 101\n001 010\n");
+
+    // the number of ones of the cauchy matrix is the same as the erasure code
+    let (k, m) = (6, 3);
+    let w = CodeWord::Other(6);
+    let x = Vec::from_iter(0..m);
+    let y = Vec::from_iter(m..m + k);
+    let bmat = BitMatrix::from(&Matrix::cauchy(gf(w), &x, &y)?);
+    let ec = ErasureCodeBuilder::new()
+        .coding_method(CodingMethod::CauchyXY)
+        .k(std::num::NonZeroI32::new(k).unwrap())
+        .m(std::num::NonZeroI32::new(m).unwrap())
+        .w(w)
+        .packet_size(std::num::NonZeroI32::new(64).unwrap())
+        .cauchy_xy(x, y)
+        .tech(Technique::BitMatrix)
+        .build()?;
+    assert_eq!(bmat.n_ones(), ec.n_ones());
+    assert_eq!(Some(bmat.into_vec()), ec.coding_bitmatrix());
+    Ok(())
+}
+
+#[test]
+fn test_display() {
+    let mat = Matrix::new(gf(CodeWord::W8), 2, 3, [1, 20, 255, 0, 3, 100]).unwrap();
+    assert_eq!(mat.to_string(), "  1  20 255\n  0   3 100\n");
+}